@@ -0,0 +1,33 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate gltf;
+
+use std::env;
+use std::path::Path;
+use std::process;
+
+use gltf::v1::Gltf;
+use gltf::v1::validation::{self, Validation};
+
+fn main() {
+    let file = match env::args().nth(1) {
+        Some(file) => file,
+        None => {
+            eprintln!("usage: gltf_validate <file.gltf>");
+            process::exit(2);
+        },
+    };
+
+    let gltf = Gltf::open(Path::new(&file)).expect("Error loading glTF asset");
+    let report = validation::validate(&gltf, Validation::Complete);
+    println!("{}", report);
+    if !report.is_valid() {
+        process::exit(1);
+    }
+}
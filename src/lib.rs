@@ -6,10 +6,14 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+extern crate base64;
+#[cfg(feature = "image")]
+extern crate image as image_crate;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
+extern crate sha2;
 
 #[macro_use]
 mod macros;
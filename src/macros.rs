@@ -13,7 +13,7 @@ macro_rules! enum_string {
     ($name:ident {
         $($variant:ident = $value:expr,)*
     }) => {
-        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
         #[repr(u32)]
         pub enum $name {
             $($variant,)*
@@ -66,7 +66,7 @@ macro_rules! enum_string {
 
 macro_rules! enum_number {
     ($name:ident { $($variant:ident = $value:expr, )* }) => {
-        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
         pub enum $name {
             $($variant = $value,)*
         }
@@ -115,3 +115,41 @@ macro_rules! enum_number {
         }
     }
 }
+
+/// Implements `References` for an object.
+///
+/// Each entry under `ids` names a property holding IDs, its JSON name, and the
+/// dictionary of `Gltf` the IDs index. Each entry under `nested` names a
+/// property holding objects that implement `References` themselves.
+macro_rules! impl_references {
+    ($ty:ty {
+        ids { $($field:ident: $name:expr => $dictionary:ident,)* }
+        nested { $($nested:ident: $nested_name:expr,)* }
+    }) => {
+        impl ::v1::validation::References for $ty {
+            fn validate_references(
+                &self,
+                gltf: &::v1::Gltf,
+                pointer: &::v1::validation::Pointer,
+                errors: &mut Vec<::v1::validation::Error>,
+            ) {
+                $(
+                    ::v1::validation::Ids::validate_ids(
+                        &self.$field,
+                        pointer.field($name),
+                        &gltf.$dictionary,
+                        errors,
+                    );
+                )*
+                $(
+                    ::v1::validation::References::validate_references(
+                        &self.$nested,
+                        gltf,
+                        &pointer.field($nested_name),
+                        errors,
+                    );
+                )*
+            }
+        }
+    }
+}
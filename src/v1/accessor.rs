@@ -6,6 +6,18 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::rc::Rc;
+use std::sync::Arc;
+use v1::Gltf;
+use v1::buffer::Buffers;
+use v1::builder::{Builder, Writable};
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+use v1::validation::{report_value, Code, Error, Pointer, Validate};
+
 enum_number! {
     ComponentType {
         I8 = 5120,
@@ -25,6 +37,18 @@ impl Default for ComponentType {
     }
 }
 
+impl ComponentType {
+    /// Returns the size of a single component in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            ComponentType::I8 | ComponentType::U8 => 1,
+            ComponentType::I16 | ComponentType::U16 => 2,
+            ComponentType::I32 | ComponentType::U32 | ComponentType::F32 => 4,
+            ComponentType::F64 => 8,
+        }
+    }
+}
+
 enum_string! {
     Kind {
         Scalar = "SCALAR",
@@ -43,6 +67,20 @@ impl Default for Kind {
     }
 }
 
+impl Kind {
+    /// Returns the number of components in a single element.
+    pub fn components(self) -> usize {
+        match self {
+            Kind::Scalar => 1,
+            Kind::Vec2 => 2,
+            Kind::Vec3 => 3,
+            Kind::Vec4 | Kind::Mat2 => 4,
+            Kind::Mat3 => 9,
+            Kind::Mat4 => 16,
+        }
+    }
+}
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Accessor {
     /// The ID of the bufferView
@@ -98,15 +136,767 @@ pub struct Accessor {
     /// have the same name, or two accessors could even have the same name.
     pub name: Option<String>,
 
-    // TODO: extension
-    // TODO: extras
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+impl Accessor {
+    /// Returns the size of a single element in bytes.
+    pub fn element_size(&self) -> usize {
+        self.kind.components() * self.component_type.size()
+    }
+
+    /// Returns the distance in bytes between the start of consecutive
+    /// elements, which is the element size when `byte_stride` is zero.
+    pub fn stride(&self) -> usize {
+        if self.byte_stride == 0 {
+            self.element_size()
+        } else {
+            self.byte_stride as usize
+        }
+    }
+
+    /// Returns a view of this accessor's data within the loaded buffers.
+    ///
+    /// Returns `None` if the referenced buffer view or buffer is missing, or
+    /// if the accessor does not fit within the buffer view.
+    pub fn view<'a>(&self, gltf: &Gltf, buffers: &'a Buffers) -> Option<View<'a>> {
+        let (buffer, range) = self.location(gltf)?;
+        Some(self.view_of(buffers.get(buffer)?.get(range)?))
+    }
+
+    /// Returns a view of this accessor's data over the bytes of its
+    /// `location`.
+    pub(crate) fn view_of<'a>(&self, data: &'a [u8]) -> View<'a> {
+        View {
+            data,
+            stride: self.stride(),
+            count: self.count as usize,
+            component_type: self.component_type,
+            components: self.kind.components(),
+        }
+    }
+
+    /// Returns the ID of the buffer holding this accessor's data and the byte
+    /// range from its first to its last element.
+    ///
+    /// Returns `None` if the buffer view is missing, or if the accessor does
+    /// not fit within it.
+    pub(crate) fn location<'a>(&self, gltf: &'a Gltf) -> Option<(&'a str, Range<usize>)> {
+        let buffer_view = gltf.buffer_views.get(&self.buffer_view)?;
+        let element_size = self.element_size();
+        let stride = self.stride();
+        if stride < element_size {
+            return None;
+        }
+        let count = self.count as usize;
+        let len = if count == 0 {
+            0
+        } else {
+            (count - 1) * stride + element_size
+        };
+        let start = buffer_view.byte_offset + self.byte_offset as usize;
+        if self.byte_offset as usize + len > buffer_view.byte_length {
+            return None;
+        }
+        Some((&buffer_view.buffer, start..start + len))
+    }
+
+    /// Computes the per-component minimum and maximum of this accessor's
+    /// elements.
+    ///
+    /// Returns `None` if the data cannot be read from `buffers` or the
+    /// accessor has no elements.
+    pub fn compute_bounds(
+        &self,
+        gltf: &Gltf,
+        buffers: &Buffers,
+    ) -> Option<(Vec<f32>, Vec<f32>)> {
+        self.view(gltf, buffers)?.bounds()
+    }
+}
+
+/// Recomputes the `min` and `max` of every accessor from its data, returning
+/// the sorted IDs of the accessors whose bounds were missing or wrong.
+///
+/// Accessors whose data cannot be read from `buffers` are left unchanged.
+pub fn update_bounds(gltf: &mut Gltf, buffers: &Buffers) -> Vec<String> {
+    let mut bounds = Vec::new();
+    for (id, accessor) in &gltf.accessors {
+        if let Some((min, max)) = accessor.compute_bounds(gltf, buffers) {
+            if accessor.min.as_ref() != Some(&min) || accessor.max.as_ref() != Some(&max) {
+                bounds.push((id.clone(), min, max));
+            }
+        }
+    }
+    let mut updated = Vec::with_capacity(bounds.len());
+    for (id, min, max) in bounds {
+        let accessor = gltf.accessors.get_mut(&id).unwrap();
+        accessor.min = Some(min);
+        accessor.max = Some(max);
+        updated.push(id);
+    }
+    updated.sort();
+    updated
+}
+
+/// The largest `byteStride` allowed by the specification.
+pub const MAX_BYTE_STRIDE: u32 = 255;
+
+impl Validate for Accessor {
+    fn validate(&self, _gltf: &Gltf, pointer: &Pointer, errors: &mut Vec<Error>) {
+        let component_type = self.component_type as u32;
+        match self.component_type {
+            ComponentType::I32 | ComponentType::F64 => {
+                report_value(errors, Code::InvalidAccessorType,
+                             pointer.field("componentType"), component_type,
+                             format!("{:?} components are not allowed in glTF 1.0",
+                                     self.component_type));
+            },
+            ComponentType::U32 if self.kind != Kind::Scalar => {
+                report_value(errors, Code::InvalidAccessorType,
+                             pointer.field("componentType"), component_type,
+                             format!("U32 components are only allowed for SCALAR \
+                                      indices, found {:?}", self.kind));
+            },
+            _ => {},
+        }
+        let size = self.component_type.size() as u32;
+        if self.byte_stride != 0 {
+            let element_size = self.element_size() as u32;
+            if self.byte_stride > MAX_BYTE_STRIDE || self.byte_stride < element_size {
+                report_value(errors, Code::OutOfRange, pointer.field("byteStride"),
+                             self.byte_stride,
+                             format!("byteStride must be between the element size {} \
+                                      and {}", element_size, MAX_BYTE_STRIDE));
+            } else if !self.byte_stride.is_multiple_of(size) {
+                report_value(errors, Code::Misaligned, pointer.field("byteStride"),
+                             self.byte_stride,
+                             format!("byteStride is not a multiple of the component \
+                                      size {}", size));
+            }
+        }
+        if !self.byte_offset.is_multiple_of(size) {
+            report_value(errors, Code::Misaligned, pointer.field("byteOffset"),
+                         self.byte_offset,
+                         format!("byteOffset is not a multiple of the component size {}",
+                                 size));
+        }
+    }
+}
+
+/// A view of an accessor's elements within a loaded buffer.
+#[derive(Clone, Copy, Debug)]
+pub struct View<'a> {
+    /// The bytes from the first to the last element inclusive.
+    data: &'a [u8],
+
+    /// The distance in bytes between the start of consecutive elements.
+    stride: usize,
+
+    /// The number of elements.
+    count: usize,
+
+    /// The datatype of each component.
+    component_type: ComponentType,
+
+    /// The number of components in each element.
+    components: usize,
+}
+
+impl<'a> View<'a> {
+    /// Computes the per-component minimum and maximum of the elements, or
+    /// `None` if there are none.
+    pub(crate) fn bounds(&self) -> Option<(Vec<f32>, Vec<f32>)> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut min = vec![f32::INFINITY; self.components()];
+        let mut max = vec![f32::NEG_INFINITY; self.components()];
+        for index in 0..self.len() {
+            for component in 0..self.components() {
+                let value = self.f32(index, component);
+                min[component] = min[component].min(value);
+                max[component] = max[component].max(value);
+            }
+        }
+        Some((min, max))
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the number of components in each element.
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the datatype of each component.
+    pub fn component_type(&self) -> ComponentType {
+        self.component_type
+    }
+
+    /// Returns the bytes of a single component.
+    ///
+    /// Panics if `index` or `component` is out of range.
+    fn bytes(&self, index: usize, component: usize) -> &'a [u8] {
+        assert!(index < self.count && component < self.components);
+        let size = self.component_type.size();
+        let start = index * self.stride + component * size;
+        &self.data[start..start + size]
+    }
+
+    /// Reads a component, converted to `f32` with `as`.
+    ///
+    /// Panics if `index` or `component` is out of range.
+    pub fn f32(&self, index: usize, component: usize) -> f32 {
+        let b = self.bytes(index, component);
+        match self.component_type {
+            ComponentType::I8 => b[0] as i8 as f32,
+            ComponentType::U8 => b[0] as f32,
+            ComponentType::I16 => i16::from_le_bytes([b[0], b[1]]) as f32,
+            ComponentType::U16 => u16::from_le_bytes([b[0], b[1]]) as f32,
+            ComponentType::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32,
+            ComponentType::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32,
+            ComponentType::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            ComponentType::F64 => {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(b);
+                f64::from_le_bytes(bytes) as f32
+            },
+        }
+    }
+
+    /// Reads a component, converted to `u32` with `as`.
+    ///
+    /// Panics if `index` or `component` is out of range.
+    pub fn u32(&self, index: usize, component: usize) -> u32 {
+        let b = self.bytes(index, component);
+        match self.component_type {
+            ComponentType::U8 => b[0] as u32,
+            ComponentType::U16 => u16::from_le_bytes([b[0], b[1]]) as u32,
+            ComponentType::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            _ => self.f32(index, component) as u32,
+        }
+    }
+
+    /// Reads the components of every element in their stored datatype, for
+    /// data whose component type is only known at runtime.
+    pub fn values(&self) -> Values {
+        match self.component_type {
+            ComponentType::I8 => Values::I8(self.collect(|b| b[0] as i8)),
+            ComponentType::U8 => Values::U8(self.collect(|b| b[0])),
+            ComponentType::I16 => {
+                Values::I16(self.collect(|b| i16::from_le_bytes([b[0], b[1]])))
+            },
+            ComponentType::U16 => {
+                Values::U16(self.collect(|b| u16::from_le_bytes([b[0], b[1]])))
+            },
+            ComponentType::I32 => {
+                Values::I32(self.collect(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+            },
+            ComponentType::U32 => {
+                Values::U32(self.collect(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+            },
+            ComponentType::F32 => {
+                Values::F32(self.collect(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+            },
+            ComponentType::F64 => Values::F64(self.collect(|b| {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(b);
+                f64::from_le_bytes(bytes)
+            })),
+        }
+    }
+
+    /// Reads every component of every element with `read`.
+    fn collect<T>(&self, read: fn(&[u8]) -> T) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.count * self.components);
+        for index in 0..self.count {
+            for component in 0..self.components {
+                values.push(read(self.bytes(index, component)));
+            }
+        }
+        values
+    }
+
+    /// Returns an iterator over the elements, each converted to `T`.
+    pub fn iter<T: Element>(&self) -> Iter<'a, T> {
+        Iter {
+            view: *self,
+            index: 0,
+            end: self.count,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the raw bytes of every element, tightly packed.
+    pub fn packed(&self) -> Vec<u8> {
+        let element_size = self.components * self.component_type.size();
+        let mut bytes = Vec::with_capacity(self.count * element_size);
+        for index in 0..self.count {
+            let start = index * self.stride;
+            bytes.extend_from_slice(&self.data[start..start + element_size]);
+        }
+        bytes
+    }
+}
+
+/// The components of an accessor's elements in their stored datatype,
+/// flattened in element order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Values {
+    /// `BYTE` components.
+    I8(Vec<i8>),
+    /// `UNSIGNED_BYTE` components.
+    U8(Vec<u8>),
+    /// `SHORT` components.
+    I16(Vec<i16>),
+    /// `UNSIGNED_SHORT` components.
+    U16(Vec<u16>),
+    /// `INT` components.
+    I32(Vec<i32>),
+    /// `UNSIGNED_INT` components.
+    U32(Vec<u32>),
+    /// `FLOAT` components.
+    F32(Vec<f32>),
+    /// `DOUBLE` components.
+    F64(Vec<f64>),
+}
+
+impl Values {
+    /// Returns the datatype of the components.
+    pub fn component_type(&self) -> ComponentType {
+        match *self {
+            Values::I8(_) => ComponentType::I8,
+            Values::U8(_) => ComponentType::U8,
+            Values::I16(_) => ComponentType::I16,
+            Values::U16(_) => ComponentType::U16,
+            Values::I32(_) => ComponentType::I32,
+            Values::U32(_) => ComponentType::U32,
+            Values::F32(_) => ComponentType::F32,
+            Values::F64(_) => ComponentType::F64,
+        }
+    }
+
+    /// Returns the number of components.
+    pub fn len(&self) -> usize {
+        match *self {
+            Values::I8(ref values) => values.len(),
+            Values::U8(ref values) => values.len(),
+            Values::I16(ref values) => values.len(),
+            Values::U16(ref values) => values.len(),
+            Values::I32(ref values) => values.len(),
+            Values::U32(ref values) => values.len(),
+            Values::F32(ref values) => values.len(),
+            Values::F64(ref values) => values.len(),
+        }
+    }
+
+    /// Returns `true` if there are no components.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the little-endian bytes of the components, tightly packed.
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.len() * self.component_type().size());
+        macro_rules! extend {
+            ($values:expr) => {
+                for value in $values {
+                    bytes.extend_from_slice(&value.to_le_bytes());
+                }
+            };
+        }
+        match *self {
+            Values::I8(ref values) => extend!(values),
+            Values::U8(ref values) => bytes.extend_from_slice(values),
+            Values::I16(ref values) => extend!(values),
+            Values::U16(ref values) => extend!(values),
+            Values::I32(ref values) => extend!(values),
+            Values::U32(ref values) => extend!(values),
+            Values::F32(ref values) => extend!(values),
+            Values::F64(ref values) => extend!(values),
+        }
+        bytes
+    }
+}
+
+/// A typed, writable view of an accessor's data within the loaded buffers.
+///
+/// Writes go straight to the buffer data, which is first copied if it is
+/// shared with other `Buffers`. The `min` and `max` of the accessor are not
+/// updated; use `update_bounds` once done.
+#[derive(Debug)]
+pub struct AccessorMut<'a, T> {
+    /// The bytes from the first to the last element inclusive.
+    data: &'a mut [u8],
+
+    /// The distance in bytes between the start of consecutive elements.
+    stride: usize,
+
+    /// The number of elements.
+    count: usize,
+
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: Writable> AccessorMut<'a, T> {
+    /// Returns a writable view of the accessor with the given ID.
+    ///
+    /// When the accessor's bytes overlap those of another accessor, they are
+    /// first copied to a new bufferView appended to `buffer` and the accessor
+    /// is patched to reference the copy, so that writes never change the
+    /// other accessor. Returns `None` if the accessor is missing, its data
+    /// cannot be read, or its component type and kind do not match `T`.
+    pub fn new(
+        gltf: &mut Gltf,
+        buffers: &'a mut Buffers,
+        id: &str,
+        buffer: &str,
+    ) -> Option<Self> {
+        let (shared, data) = {
+            let accessor = gltf.accessors.get(id)?;
+            if accessor.component_type != T::COMPONENT_TYPE || accessor.kind != T::KIND {
+                return None;
+            }
+            let data = accessor.view(gltf, buffers)?.packed();
+            let (buffer, range) = accessor.location(gltf)?;
+            let shared = gltf.accessors.iter()
+                .filter(|&(other, _)| other != id)
+                .filter_map(|(_, other)| other.location(gltf))
+                .any(|(other_buffer, other)| {
+                    let overlaps = other.start < range.end && range.start < other.end;
+                    other_buffer == buffer && overlaps
+                });
+            (shared, data)
+        };
+        if shared {
+            let target = gltf.buffer_views[&gltf.accessors[id].buffer_view].target;
+            let mut builder = Builder::new(gltf, buffers, buffer);
+            let view = builder.buffer_view(&format!("{}_view", id), &data, target);
+            let accessor = builder.gltf().accessors.get_mut(id).unwrap();
+            accessor.buffer_view = view;
+            accessor.byte_offset = 0;
+            accessor.byte_stride = 0;
+        }
+        let accessor = &gltf.accessors[id];
+        let (buffer, range) = accessor.location(gltf)?;
+        Some(AccessorMut {
+            data: &mut Arc::make_mut(buffers.get_mut(buffer)?)[range],
+            stride: accessor.stride(),
+            count: accessor.count as usize,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the accessor has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Overwrites the element at `index`.
+    ///
+    /// Panics if `index` is out of range.
+    pub fn set(&mut self, index: usize, value: T) {
+        assert!(index < self.count);
+        let mut bytes = Vec::new();
+        value.write(&mut bytes);
+        let start = index * self.stride;
+        self.data[start..start + bytes.len()].copy_from_slice(&bytes);
+    }
+}
+
+impl<'a, T: Writable + Element> AccessorMut<'a, T> {
+    /// Reads the element at `index`.
+    ///
+    /// Panics if `index` is out of range.
+    pub fn get(&self, index: usize) -> T {
+        T::read(&self.view(), index)
+    }
+
+    /// Replaces every element with the result of `f`.
+    pub fn update<F: FnMut(T) -> T>(&mut self, mut f: F) {
+        for index in 0..self.count {
+            let value = f(self.get(index));
+            self.set(index, value);
+        }
+    }
+
+    fn view(&self) -> View<'_> {
+        View {
+            data: self.data,
+            stride: self.stride,
+            count: self.count,
+            component_type: T::COMPONENT_TYPE,
+            components: T::KIND.components(),
+        }
+    }
 }
 
+/// A type that an accessor element can be read as.
+///
+/// Components missing from the accessor are read as zero, and surplus
+/// components are ignored.
+pub trait Element: Sized {
+    /// Reads the element at `index`.
+    fn read(view: &View, index: usize) -> Self;
+}
+
+impl Element for f32 {
+    fn read(view: &View, index: usize) -> Self {
+        view.f32(index, 0)
+    }
+}
+
+impl Element for u32 {
+    fn read(view: &View, index: usize) -> Self {
+        view.u32(index, 0)
+    }
+}
+
+macro_rules! impl_element_array {
+    ($ty:ty, $n:expr, $method:ident) => {
+        impl Element for [$ty; $n] {
+            fn read(view: &View, index: usize) -> Self {
+                let mut element = [Default::default(); $n];
+                let n = view.components().min($n);
+                for (c, value) in element.iter_mut().enumerate().take(n) {
+                    *value = view.$method(index, c);
+                }
+                element
+            }
+        }
+    }
+}
+
+impl_element_array!(f32, 2, f32);
+impl_element_array!(f32, 3, f32);
+impl_element_array!(f32, 4, f32);
+impl_element_array!(f32, 16, f32);
+impl_element_array!(u32, 4, u32);
+
+/// Memoizes decoded accessor data by accessor ID.
+///
+/// Utilities that walk a whole scene meet the same accessor once for every
+/// primitive and node instance sharing it; reading through a cache decodes
+/// each accessor only once.
+pub(crate) struct Cache<'a, T> {
+    gltf: &'a Gltf,
+    buffers: &'a Buffers,
+    data: HashMap<String, Option<Rc<[T]>>>,
+}
+
+impl<'a, T: Element> Cache<'a, T> {
+    /// Creates an empty cache.
+    pub fn new(gltf: &'a Gltf, buffers: &'a Buffers) -> Self {
+        Cache {
+            gltf,
+            buffers,
+            data: HashMap::new(),
+        }
+    }
+
+    /// Returns the elements of the accessor with the given ID, or `None` if
+    /// they cannot be read from the buffers.
+    pub fn read(&mut self, id: &str) -> Option<Rc<[T]>> {
+        if let Some(data) = self.data.get(id) {
+            return data.clone();
+        }
+        let data = self.gltf.accessors.get(id)
+            .and_then(|accessor| accessor.view(self.gltf, self.buffers))
+            .map(|view| view.iter().collect());
+        self.data.insert(id.to_string(), data.clone());
+        data
+    }
+}
+
+/// An `Iterator` over the elements of an accessor.
+#[derive(Clone, Debug)]
+pub struct Iter<'a, T> {
+    /// The accessor data.
+    view: View<'a>,
+
+    /// The index of the next element from the front.
+    index: usize,
+
+    /// One past the index of the next element from the back.
+    end: usize,
+
+    /// Consumes the `T` type parameter without tying `Send` and `Sync` to it.
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: Element> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < self.end {
+            let element = T::read(&self.view, self.index);
+            self.index += 1;
+            Some(element)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.index;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.index = self.index.saturating_add(n).min(self.end);
+        self.next()
+    }
+}
+
+impl<'a, T: Element> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index < self.end {
+            self.end -= 1;
+            Some(T::read(&self.view, self.end))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T: Element> ExactSizeIterator for Iter<'a, T> {}
+
+impl_references!(Accessor {
+    ids { buffer_view: "bufferView" => buffer_views, }
+    nested {}
+});
+
 #[cfg(test)]
 mod test {
     extern crate serde_json;
     use super::*;
 
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn views_are_send_and_sync() {
+        assert_send_sync::<View>();
+        assert_send_sync::<Iter<[f32; 3]>>();
+    }
+
+    #[test]
+    fn it_decodes_cached_accessors_once() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "values": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 2, "type": "SCALAR"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteLength": 8 }
+            }
+        }"#).unwrap();
+        let mut buffers = Buffers::new();
+        buffers.insert("buffer".to_string(),
+                       [1.0f32, 2.0].iter().flat_map(|x| x.to_le_bytes()).collect());
+        let mut cache = Cache::<f32>::new(&gltf, &buffers);
+        let first = cache.read("values").unwrap();
+        let second = cache.read("values").unwrap();
+        assert_eq!(&[1.0, 2.0], &first[..]);
+        assert!(Rc::ptr_eq(&first, &second));
+        assert!(cache.read("missing").is_none());
+    }
+
+    #[test]
+    fn it_copies_shared_data_before_writing() {
+        let mut gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "a": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 2, "type": "SCALAR"
+                },
+                "b": {
+                    "bufferView": "view", "byteOffset": 4,
+                    "componentType": 5126, "count": 1, "type": "SCALAR"
+                },
+                "c": {
+                    "bufferView": "view", "byteOffset": 8,
+                    "componentType": 5126, "count": 1, "type": "SCALAR"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteLength": 12 }
+            }
+        }"#).unwrap();
+        let mut buffers = Buffers::new();
+        let data = [1.0f32, 2.0, 3.0];
+        buffers.insert("buffer".to_string(),
+                       data.iter().flat_map(|x| x.to_le_bytes()).collect());
+
+        let mut c = AccessorMut::new(&mut gltf, &mut buffers, "c", "copies").unwrap();
+        c.update(|x: f32| x * 10.0);
+        assert_eq!("view", gltf.accessors["c"].buffer_view);
+        let mut a = AccessorMut::new(&mut gltf, &mut buffers, "a", "copies").unwrap();
+        a.set(1, -2.0f32);
+        assert_eq!(-2.0, a.get(1));
+        assert_eq!("a_view", gltf.accessors["a"].buffer_view);
+
+        let read = |id: &str| -> Vec<f32> {
+            gltf.accessors[id].view(&gltf, &buffers).unwrap().iter().collect()
+        };
+        assert_eq!(vec![1.0, -2.0], read("a"));
+        assert_eq!(vec![2.0], read("b"));
+        assert_eq!(vec![30.0], read("c"));
+        let vec3 = AccessorMut::<[f32; 3]>::new(&mut gltf, &mut buffers, "b", "copies");
+        assert!(vec3.is_none());
+    }
+
+    #[test]
+    fn it_repairs_bounds() {
+        let mut gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "wrong": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 2, "type": "VEC2",
+                    "min": [0, 0], "max": [1, 1]
+                },
+                "missing": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 4, "type": "SCALAR"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteLength": 16 }
+            }
+        }"#).unwrap();
+        let mut buffers = Buffers::new();
+        let data = [-1.0f32, 2.0, 3.0, 0.5];
+        buffers.insert("buffer".to_string(),
+                       data.iter().flat_map(|x| x.to_le_bytes()).collect());
+        assert_eq!(vec!["missing", "wrong"], update_bounds(&mut gltf, &buffers));
+        assert_eq!(Some(vec![-1.0, 0.5]), gltf.accessors["wrong"].min);
+        assert_eq!(Some(vec![3.0, 2.0]), gltf.accessors["wrong"].max);
+        assert!(update_bounds(&mut gltf, &buffers).is_empty());
+    }
+
     #[test]
     fn invalid_component_type() {
         let data = r#"{
@@ -161,5 +951,69 @@ mod test {
         assert_eq!(1024, accessor.count);
         assert_eq!(Kind::Scalar, accessor.kind);
         assert_eq!(3, accessor.max.unwrap().len());
+        assert!(accessor.extensions.contains("extension_name"));
+        assert_eq!(1, accessor.extensions.len());
+    }
+
+    #[test]
+    fn it_reads_strided_elements() {
+        let data = r#"{
+    "accessors": {
+        "uvs": {
+            "bufferView": "view",
+            "byteOffset": 4,
+            "byteStride": 8,
+            "componentType": 5123,
+            "count": 3,
+            "type": "VEC2"
+        }
+    },
+    "bufferViews": {
+        "view": { "buffer": "buffer", "byteOffset": 2, "byteLength": 24 }
+    }
+}"#;
+        let gltf: Gltf = serde_json::from_str(data).unwrap();
+        let mut buffers = Buffers::new();
+        let bytes = (0..26u8).collect::<Vec<_>>();
+        buffers.insert("buffer".to_string(), bytes.into());
+        let view = gltf.accessors["uvs"].view(&gltf, &buffers).unwrap();
+        let uvs: Vec<[u32; 4]> = view.iter().collect();
+        assert_eq!(vec![
+            [0x0706, 0x0908, 0, 0],
+            [0x0f0e, 0x1110, 0, 0],
+            [0x1716, 0x1918, 0, 0],
+        ], uvs);
+
+        let mut iter = view.iter::<[u32; 4]>();
+        assert_eq!(Some([0x1716, 0x1918, 0, 0]), iter.next_back());
+        assert_eq!(Some([0x0f0e, 0x1110, 0, 0]), iter.nth(1));
+        assert_eq!(0, iter.len());
+        assert_eq!(None, iter.next_back());
+
+        let truncated = buffers["buffer"][..25].into();
+        buffers.insert("buffer".to_string(), truncated);
+        assert!(gltf.accessors["uvs"].view(&gltf, &buffers).is_none());
+    }
+
+    #[test]
+    fn it_validates_byte_stride() {
+        let accessor = |stride: u32| Accessor {
+            byte_stride: stride,
+            component_type: ComponentType::F32,
+            kind: Kind::Vec3,
+            ..Default::default()
+        };
+        let codes = |stride: u32| {
+            let mut errors = Vec::new();
+            accessor(stride).validate(&Gltf::default(), &Pointer::root(), &mut errors);
+            errors.into_iter().map(|error| error.code).collect::<Vec<_>>()
+        };
+        assert!(codes(0).is_empty());
+        assert!(codes(16).is_empty());
+        assert_eq!(vec![Code::OutOfRange], codes(8));
+        assert_eq!(vec![Code::OutOfRange], codes(256));
+        assert_eq!(vec![Code::Misaligned], codes(14));
+        assert_eq!(12, accessor(0).stride());
+        assert_eq!(16, accessor(16).stride());
     }
 }
@@ -0,0 +1,618 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::HashMap;
+use v1::Gltf;
+use v1::accessor;
+use v1::accessor::{ComponentType, Kind};
+use v1::buffer::Buffers;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+use v1::node::Node;
+use v1::validation::{report, report_value, Code, Error, Pointer, Validate};
+
+pub mod sample;
+
+enum_string! {
+    TargetPath {
+        Translation = "translation",
+        Rotation = "rotation",
+        Scale = "scale",
+    }
+}
+
+enum_string! {
+    Interpolation {
+        Linear = "LINEAR",
+        Step = "STEP",
+    }
+}
+
+impl Default for Interpolation {
+    fn default() -> Interpolation {
+        Interpolation::Linear
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Target {
+    /// The ID of the node to target.
+    pub id: String,
+
+    /// The name of the node's TRS property to modify.
+    pub path: TargetPath,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Channel {
+    /// The ID of a sampler in this animation used to compute the value for the
+    /// target, e.g., a node's translation, rotation, or scale (TRS).
+    pub sampler: String,
+
+    /// The ID of the node and TRS property to target.
+    pub target: Target,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+impl Channel {
+    /// Returns the node targeted by this channel.
+    pub fn node<'a>(&self, gltf: &'a Gltf) -> Option<&'a Node> {
+        gltf.nodes.get(&self.target.id)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Sampler {
+    /// The ID of a parameter in this animation to use as keyframe input.
+    ///
+    /// This parameter must have type FLOAT. The values represent time in
+    /// seconds with `time[0] >= 0.0`, and monotonically increasing values,
+    /// i.e., time[n + 1] >= time[n]
+    pub input: String,
+
+    /// Interpolation algorithm.
+    ///
+    /// When an animation targets a node's rotation, and the animation's
+    /// interpolation is "LINEAR", spherical linear interpolation (slerp) should
+    /// be used to interpolate quaternions.
+    #[serde(default)]
+    pub interpolation: Interpolation,
+
+    /// The ID of a parameter in this animation to use as keyframe output.
+    pub output: String,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Animation {
+    /// An array of channels, each of which targets an animation's sampler at a
+    /// node's property.
+    #[serde(default)]
+    pub channels: Vec<Channel>,
+
+    /// A dictionary object of strings whose values are IDs of accessors with
+    /// keyframe data, e.g., time, translation, rotation, etc.
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+
+    /// A dictionary object of animation.sampler objects that combines input and
+    /// output parameters with an interpolation algorithm to define a keyframe
+    /// graph (but not its target).
+    #[serde(default)]
+    pub samplers: HashMap<String, Sampler>,
+
+    /// The user-defined name of this object.
+    ///
+    /// This is not necessarily unique, e.g., an animation and a buffer could
+    /// have the same name, or two animations could even have the same name.
+    pub name: Option<String>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+/// Keyframe output values, matched to the path of the targeted property.
+#[derive(Clone, Debug)]
+pub enum Outputs<'a> {
+    /// XYZ translations.
+    Translations(accessor::Iter<'a, [f32; 3]>),
+
+    /// XYZW rotation quaternions.
+    Rotations(accessor::Iter<'a, [f32; 4]>),
+
+    /// XYZ scales.
+    Scales(accessor::Iter<'a, [f32; 3]>),
+}
+
+impl Animation {
+    /// Returns the ID of the accessor holding the named parameter.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters.get(name).map(String::as_str)
+    }
+
+    /// Returns the sampler used by the given channel of this animation.
+    pub fn sampler(&self, channel: &Channel) -> Option<&Sampler> {
+        self.samplers.get(&channel.sampler)
+    }
+
+    /// Reads the keyframe times of a sampler of this animation, in seconds.
+    pub fn read_inputs<'a>(
+        &self,
+        sampler: &Sampler,
+        gltf: &Gltf,
+        buffers: &'a Buffers,
+    ) -> Option<accessor::Iter<'a, f32>> {
+        let accessor = gltf.accessors.get(self.parameter(&sampler.input)?)?;
+        accessor.view(gltf, buffers).map(|view| view.iter())
+    }
+
+    /// Reads the keyframe times of a channel of this animation, in seconds.
+    pub fn read_times<'a>(
+        &self,
+        channel: &Channel,
+        gltf: &Gltf,
+        buffers: &'a Buffers,
+    ) -> Option<accessor::Iter<'a, f32>> {
+        self.read_inputs(self.sampler(channel)?, gltf, buffers)
+    }
+
+    /// Returns the length of this animation in seconds, i.e. the latest
+    /// keyframe time of any sampler.
+    ///
+    /// The times are read from the `max` property of the input accessors, so
+    /// `None` is returned if any of them is missing or omits it.
+    pub fn duration(&self, gltf: &Gltf) -> Option<f32> {
+        let mut duration = 0.0f32;
+        for sampler in self.samplers.values() {
+            let accessor = gltf.accessors.get(self.parameter(&sampler.input)?)?;
+            let max = accessor.max.as_ref()?.first()?;
+            duration = duration.max(*max);
+        }
+        Some(duration)
+    }
+
+    /// Remaps this animation from the nodes of `source` onto the nodes of
+    /// `target` with the same name.
+    ///
+    /// Nodes are matched by `name`, or by `jointName` for unnamed nodes.
+    /// Channels targeting nodes without a unique match in `target` are dropped,
+    /// along with samplers no longer used by any channel. The parameters still
+    /// refer to the accessors of `source`, which must be copied into `target`
+    /// alongside the animation.
+    pub fn retarget(&self, source: &Gltf, target: &Gltf) -> Animation {
+        let mut ids: HashMap<&str, Option<&str>> = HashMap::new();
+        for (id, node) in &target.nodes {
+            if let Some(name) = node_name(node) {
+                ids.entry(name)
+                    .and_modify(|id| *id = None)
+                    .or_insert(Some(id.as_str()));
+            }
+        }
+        let mut animation = self.clone();
+        animation.channels.clear();
+        for channel in &self.channels {
+            let name = source.nodes.get(&channel.target.id).and_then(node_name);
+            if let Some(&Some(id)) = name.and_then(|name| ids.get(name)) {
+                let mut channel = channel.clone();
+                channel.target.id = id.to_string();
+                animation.channels.push(channel);
+            }
+        }
+        let channels = &animation.channels;
+        animation.samplers.retain(|id, _| channels.iter().any(|c| &c.sampler == id));
+        animation
+    }
+
+    /// Reads the keyframe values of a channel of this animation.
+    pub fn read_outputs<'a>(
+        &self,
+        channel: &Channel,
+        gltf: &Gltf,
+        buffers: &'a Buffers,
+    ) -> Option<Outputs<'a>> {
+        let sampler = self.sampler(channel)?;
+        let accessor = gltf.accessors.get(self.parameter(&sampler.output)?)?;
+        let view = accessor.view(gltf, buffers)?;
+        Some(match channel.target.path {
+            TargetPath::Translation => Outputs::Translations(view.iter()),
+            TargetPath::Rotation => Outputs::Rotations(view.iter()),
+            TargetPath::Scale => Outputs::Scales(view.iter()),
+        })
+    }
+}
+
+/// Returns the name used to match nodes across assets.
+fn node_name(node: &Node) -> Option<&str> {
+    node.name.as_deref().or(node.joint_name.as_deref())
+}
+
+impl Validate for Animation {
+    fn validate(&self, gltf: &Gltf, pointer: &Pointer, errors: &mut Vec<Error>) {
+        for (i, channel) in self.channels.iter().enumerate() {
+            let pointer = pointer.field("channels").index(i);
+            let target = pointer.field("target");
+            if channel.node(gltf).is_some_and(|node| node.matrix.is_some()) {
+                report_value(errors, Code::Conflict, target.field("id"),
+                             channel.target.id.as_str(),
+                             "node defines a matrix and cannot be animated".to_string());
+            }
+            let sampler = match self.sampler(channel) {
+                Some(sampler) => sampler,
+                None => {
+                    report_value(errors, Code::MissingReference, pointer.field("sampler"),
+                                 channel.sampler.as_str(),
+                                 "sampler does not exist".to_string());
+                    continue;
+                },
+            };
+            let output = self.parameter(&sampler.output)
+                .and_then(|id| gltf.accessors.get(id));
+            let expected = match channel.target.path {
+                TargetPath::Rotation => Kind::Vec4,
+                TargetPath::Translation | TargetPath::Scale => Kind::Vec3,
+            };
+            if let Some(output) = output {
+                let float = output.component_type == ComponentType::F32;
+                if output.kind != expected || !float {
+                    report(errors, Code::InvalidAccessorType, target.field("path"),
+                           format!("{:?} output must be float {:?}, found {:?} {:?}",
+                                   channel.target.path, expected,
+                                   output.component_type, output.kind));
+                }
+            }
+        }
+
+        for (id, sampler) in &self.samplers {
+            let pointer = pointer.field("samplers").field(id);
+            let input_pointer = pointer.field("input");
+            let output_pointer = pointer.field("output");
+            let input =
+                self.validate_parameter(gltf, &input_pointer, &sampler.input, errors);
+            let output =
+                self.validate_parameter(gltf, &output_pointer, &sampler.output, errors);
+            if let Some(input) = input {
+                let float = input.component_type == ComponentType::F32;
+                if input.kind != Kind::Scalar || !float {
+                    report(errors, Code::InvalidAccessorType, input_pointer.clone(),
+                           format!("input must be float SCALAR, found {:?} {:?}",
+                                   input.component_type, input.kind));
+                }
+                if input.min.is_none() || input.max.is_none() {
+                    report(errors, Code::MissingProperty, input_pointer,
+                           "input accessor must define min and max".to_string());
+                }
+            }
+            if let (Some(input), Some(output)) = (input, output) {
+                if input.count != output.count {
+                    report(errors, Code::CountMismatch, pointer.clone(),
+                           format!("input has {} keyframes but output has {}",
+                                   input.count, output.count));
+                }
+            }
+        }
+    }
+}
+
+impl Animation {
+    /// Checks that a sampler parameter exists, returning its accessor.
+    ///
+    /// A parameter naming a missing accessor is reported by the reference
+    /// checks rather than here.
+    fn validate_parameter<'a>(
+        &self,
+        gltf: &'a Gltf,
+        pointer: &Pointer,
+        parameter: &str,
+        errors: &mut Vec<Error>,
+    ) -> Option<&'a accessor::Accessor> {
+        match self.parameter(parameter) {
+            Some(id) => gltf.accessors.get(id),
+            None => {
+                report_value(errors, Code::MissingReference, pointer.clone(), parameter,
+                             "parameter does not exist".to_string());
+                None
+            },
+        }
+    }
+}
+
+impl_references!(Target {
+    ids { id: "id" => nodes, }
+    nested {}
+});
+
+impl_references!(Channel {
+    ids {}
+    nested { target: "target", }
+});
+
+impl_references!(Animation {
+    ids { parameters: "parameters" => accessors, }
+    nested { channels: "channels", }
+});
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+    use v1::Gltf;
+    use v1::buffer::Buffers;
+    use v1::validation::{self, Validation};
+
+    #[test]
+    fn it_deserializes_an_animation() {
+        let data = r#"{
+    "channels": [
+        {
+            "sampler": "a_sampler",
+            "target": {
+                "id": "node_id",
+                "path": "rotation",
+                "extensions": {
+                    "extension_name": {
+                        "extension specific": "value"
+                    }
+                },
+                "extras": {
+                    "Application specific": "The extra object can contain any properties."
+                }
+            },
+            "extensions": {
+                "extension_name": {
+                    "extension specific": "value"
+                }
+            },
+            "extras": {
+                "Application specific": "The extra object can contain any properties."
+            }
+        }
+    ],
+    "name": "user-defined animation name",
+    "parameters": {
+        "TIME": "time_accessor",
+        "rotation": "rotation_accessor"
+    },
+    "samplers": {
+        "a_sampler": {
+            "input": "TIME",
+            "interpolation": "LINEAR",
+            "output": "rotation",
+            "extensions": {
+                "extension_name": {
+                    "extension specific": "value"
+                }
+            },
+            "extras": {
+                "Application specific": "The extra object can contain any properties."
+            }
+        }
+    },
+    "extensions": {
+        "extension_name": {
+            "extension specific": "value"
+        }
+    },
+    "extras": {
+        "Application specific": "The extra object can contain any properties."
+    }
+}"#;
+        let animation: Animation = serde_json::from_str(data).unwrap();
+
+        assert_eq!(Some("user-defined animation name"), animation.name.as_deref());
+        assert_eq!(2, animation.parameters.len());
+        assert_eq!(Some("rotation_accessor"), animation.parameter("rotation"));
+        let sampler = animation.sampler(&animation.channels[0]).unwrap();
+        assert_eq!(Interpolation::Linear, sampler.interpolation);
+    }
+
+    #[test]
+    fn it_reads_keyframes() {
+        let data = r#"{
+    "accessors": {
+        "time": {
+            "bufferView": "view",
+            "byteOffset": 0,
+            "componentType": 5126,
+            "count": 2,
+            "type": "SCALAR",
+            "min": [0.0],
+            "max": [1.5]
+        },
+        "scale": {
+            "bufferView": "view",
+            "byteOffset": 8,
+            "componentType": 5126,
+            "count": 2,
+            "type": "VEC3"
+        }
+    },
+    "animations": {
+        "grow": {
+            "channels": [{
+                "sampler": "sampler",
+                "target": { "id": "node", "path": "scale" }
+            }],
+            "parameters": { "TIME": "time", "scale": "scale" },
+            "samplers": {
+                "sampler": { "input": "TIME", "output": "scale" }
+            }
+        }
+    },
+    "buffers": {
+        "buffer": { "uri": "keyframes.bin", "byteLength": 32 }
+    },
+    "bufferViews": {
+        "view": { "buffer": "buffer", "byteOffset": 0, "byteLength": 32 }
+    },
+    "nodes": {
+        "node": {}
+    }
+}"#;
+        let gltf: Gltf = serde_json::from_str(data).unwrap();
+        let mut bytes = Vec::new();
+        for x in &[0.0f32, 1.5, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0] {
+            bytes.extend_from_slice(&x.to_le_bytes());
+        }
+        let mut buffers = Buffers::new();
+        buffers.insert("buffer".to_string(), bytes.into());
+
+        let animation = &gltf.animations["grow"];
+        let channel = &animation.channels[0];
+        assert!(channel.node(&gltf).is_some());
+        let sampler = animation.sampler(channel).unwrap();
+        let times: Vec<f32> = animation
+            .read_inputs(sampler, &gltf, &buffers)
+            .unwrap()
+            .collect();
+        assert_eq!(vec![0.0, 1.5], times);
+        assert_eq!(2, animation.read_times(channel, &gltf, &buffers).unwrap().len());
+        assert_eq!(Some(1.5), animation.duration(&gltf));
+        assert!(validation::validate(&gltf, Validation::Complete).is_valid());
+        match animation.read_outputs(channel, &gltf, &buffers).unwrap() {
+            Outputs::Scales(scales) => {
+                assert_eq!(vec![[1.0; 3], [2.0; 3]], scales.collect::<Vec<_>>());
+            },
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn it_retargets_by_node_name() {
+        let source = r#"{
+    "animations": {
+        "walk": {
+            "channels": [
+                { "sampler": "a", "target": { "id": "n0", "path": "rotation" } },
+                { "sampler": "b", "target": { "id": "n1", "path": "rotation" } },
+                { "sampler": "c", "target": { "id": "n2", "path": "rotation" } }
+            ],
+            "parameters": { "TIME": "time", "r": "rotations" },
+            "samplers": {
+                "a": { "input": "TIME", "output": "r" },
+                "b": { "input": "TIME", "output": "r" },
+                "c": { "input": "TIME", "output": "r" }
+            }
+        }
+    },
+    "nodes": {
+        "n0": { "name": "hip" },
+        "n1": { "jointName": "knee" },
+        "n2": { "name": "tail" }
+    }
+}"#;
+        let target = r#"{
+    "nodes": {
+        "hip_node": { "name": "hip" },
+        "knee_node": { "jointName": "knee" },
+        "tail_1": { "name": "tail" },
+        "tail_2": { "name": "tail" }
+    }
+}"#;
+        let source: Gltf = serde_json::from_str(source).unwrap();
+        let target: Gltf = serde_json::from_str(target).unwrap();
+        let animation = source.animations["walk"].retarget(&source, &target);
+        let ids: Vec<&str> = animation.channels
+            .iter()
+            .map(|channel| channel.target.id.as_str())
+            .collect();
+        assert_eq!(vec!["hip_node", "knee_node"], ids);
+        assert_eq!(2, animation.samplers.len());
+        assert!(!animation.samplers.contains_key("c"));
+    }
+
+    #[test]
+    fn it_validates_channels() {
+        let data = r#"{
+    "accessors": {
+        "time": {
+            "bufferView": "view",
+            "byteOffset": 0,
+            "componentType": 5123,
+            "count": 2,
+            "type": "SCALAR"
+        },
+        "rotation": {
+            "bufferView": "view",
+            "byteOffset": 0,
+            "componentType": 5126,
+            "count": 3,
+            "type": "VEC3"
+        }
+    },
+    "animations": {
+        "spin": {
+            "channels": [
+                { "sampler": "a", "target": { "id": "node", "path": "rotation" } },
+                { "sampler": "b", "target": { "id": "missing", "path": "scale" } },
+                { "sampler": "a", "target": { "id": "fixed", "path": "rotation" } }
+            ],
+            "parameters": { "TIME": "time", "rotation": "rotation" },
+            "samplers": {
+                "a": { "input": "TIME", "output": "rotation" }
+            }
+        }
+    },
+    "nodes": {
+        "node": {},
+        "fixed": { "matrix": [1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1] }
+    }
+}"#;
+        let gltf: Gltf = serde_json::from_str(data).unwrap();
+        let mut pointers: Vec<String> = gltf.validate()
+            .into_iter()
+            .map(|error| error.pointer.to_string())
+            .filter(|pointer| pointer.starts_with("/animations/"))
+            .collect();
+        pointers.sort();
+        assert_eq!(vec![
+            "/animations/spin/channels/0/target/path",
+            "/animations/spin/channels/1/sampler",
+            "/animations/spin/channels/1/target/id",
+            "/animations/spin/channels/2/target/id",
+            "/animations/spin/channels/2/target/path",
+            "/animations/spin/samplers/a",
+            "/animations/spin/samplers/a/input",
+            "/animations/spin/samplers/a/input",
+        ], pointers);
+    }
+}
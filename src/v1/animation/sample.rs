@@ -0,0 +1,208 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Evaluation of animations at arbitrary points in time.
+
+use std::collections::HashMap;
+use v1::Gltf;
+use v1::animation::{Animation, Interpolation, Outputs};
+use v1::buffer::Buffers;
+use v1::math;
+
+/// Transform properties of a node overridden by an animation.
+///
+/// Properties not targeted by any channel are `None`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pose {
+    /// XYZ translation.
+    pub translation: Option<[f32; 3]>,
+
+    /// XYZW unit quaternion rotation.
+    pub rotation: Option<[f32; 4]>,
+
+    /// XYZ scale.
+    pub scale: Option<[f32; 3]>,
+}
+
+/// An animation with its keyframes read into memory, ready for sampling.
+#[derive(Clone, Debug)]
+pub struct Clip {
+    /// One track per channel.
+    tracks: Vec<Track>,
+}
+
+#[derive(Clone, Debug)]
+struct Track {
+    /// The ID of the targeted node.
+    node: String,
+
+    /// Keyframe times in seconds.
+    times: Vec<f32>,
+
+    /// Keyframe values.
+    values: Values,
+
+    /// Interpolation between keyframes.
+    interpolation: Interpolation,
+}
+
+#[derive(Clone, Debug)]
+enum Values {
+    Translations(Vec<[f32; 3]>),
+    Rotations(Vec<[f32; 4]>),
+    Scales(Vec<[f32; 3]>),
+}
+
+impl Clip {
+    /// Reads the keyframes of every channel of an animation.
+    ///
+    /// Returns `None` if the keyframes of any channel cannot be read.
+    pub fn new(animation: &Animation, gltf: &Gltf, buffers: &Buffers) -> Option<Clip> {
+        let mut tracks = Vec::with_capacity(animation.channels.len());
+        for channel in &animation.channels {
+            let sampler = animation.sampler(channel)?;
+            let times = animation.read_inputs(sampler, gltf, buffers)?.collect();
+            let values = match animation.read_outputs(channel, gltf, buffers)? {
+                Outputs::Translations(iter) => Values::Translations(iter.collect()),
+                Outputs::Rotations(iter) => Values::Rotations(iter.collect()),
+                Outputs::Scales(iter) => Values::Scales(iter.collect()),
+            };
+            tracks.push(Track {
+                node: channel.target.id.clone(),
+                times,
+                values,
+                interpolation: sampler.interpolation,
+            });
+        }
+        Some(Clip { tracks })
+    }
+
+    /// Returns the latest keyframe time of any channel, in seconds.
+    pub fn duration(&self) -> f32 {
+        self.tracks
+            .iter()
+            .filter_map(|track| track.times.last())
+            .fold(0.0, |duration, &t| duration.max(t))
+    }
+
+    /// Evaluates every channel at time `t` in seconds, returning the
+    /// overridden properties of each targeted node, keyed by node ID.
+    ///
+    /// Times before the first keyframe or after the last keyframe of a channel
+    /// are clamped.
+    pub fn sample(&self, t: f32) -> HashMap<String, Pose> {
+        let mut poses: HashMap<String, Pose> = HashMap::new();
+        for track in &self.tracks {
+            let pose = poses.entry(track.node.clone()).or_default();
+            track.apply(t, pose);
+        }
+        poses
+    }
+}
+
+impl Track {
+    fn apply(&self, t: f32, pose: &mut Pose) {
+        let (i, j, s) = match self.keyframes(t) {
+            Some(keyframes) => keyframes,
+            None => return,
+        };
+        match self.values {
+            Values::Translations(ref values) => {
+                pose.translation = self.lerp(values, i, j, s);
+            },
+            Values::Scales(ref values) => {
+                pose.scale = self.lerp(values, i, j, s);
+            },
+            Values::Rotations(ref values) => {
+                pose.rotation = match (values.get(i), values.get(j)) {
+                    (Some(&a), Some(&b)) if self.interpolation == Interpolation::Linear => {
+                        Some(math::slerp(a, b, s))
+                    },
+                    (Some(&a), _) => Some(a),
+                    _ => None,
+                };
+            },
+        }
+    }
+
+    fn lerp(&self, values: &[[f32; 3]], i: usize, j: usize, s: f32) -> Option<[f32; 3]> {
+        match (values.get(i), values.get(j)) {
+            (Some(&a), Some(&b)) if self.interpolation == Interpolation::Linear => {
+                Some(math::lerp(a, b, s))
+            },
+            (Some(&a), _) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Returns the keyframes surrounding time `t` and the interpolation
+    /// factor between them.
+    fn keyframes(&self, t: f32) -> Option<(usize, usize, f32)> {
+        let last = self.times.len().checked_sub(1)?;
+        if t <= self.times[0] {
+            return Some((0, 0, 0.0));
+        }
+        if t >= self.times[last] {
+            return Some((last, last, 0.0));
+        }
+        // The first keyframe after `t`; always in 1..=last here.
+        let j = self.times.iter().position(|&time| time > t)?;
+        let i = j - 1;
+        let span = self.times[j] - self.times[i];
+        let s = if span > 0.0 { (t - self.times[i]) / span } else { 0.0 };
+        Some((i, j, s))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn clip(interpolation: Interpolation) -> Clip {
+        let s = 0.5f32.sqrt();
+        Clip {
+            tracks: vec![
+                Track {
+                    node: "a".to_string(),
+                    times: vec![1.0, 2.0, 4.0],
+                    values: Values::Translations(vec![[0.0; 3], [2.0; 3], [4.0; 3]]),
+                    interpolation,
+                },
+                Track {
+                    node: "a".to_string(),
+                    times: vec![0.0, 1.0],
+                    values: Values::Rotations(vec![[0.0, 0.0, 0.0, 1.0], [0.0, 0.0, s, s]]),
+                    interpolation,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn it_interpolates_linearly() {
+        let pose = clip(Interpolation::Linear).sample(3.0)["a"];
+        assert_eq!(Some([3.0; 3]), pose.translation);
+        assert_eq!(None, pose.scale);
+
+        let pose = clip(Interpolation::Linear).sample(0.5)["a"];
+        assert_eq!(Some([0.0; 3]), pose.translation);
+        let rotation = pose.rotation.unwrap();
+        let expected = (22.5f32).to_radians();
+        assert!((rotation[2] - expected.sin()).abs() < 1e-5);
+        assert!((rotation[3] - expected.cos()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn it_steps_and_clamps() {
+        let clip = clip(Interpolation::Step);
+        assert_eq!(4.0, clip.duration());
+        assert_eq!(Some([2.0; 3]), clip.sample(3.9)["a"].translation);
+        assert_eq!(Some([4.0; 3]), clip.sample(10.0)["a"].translation);
+        assert_eq!(Some([0.0, 0.0, 0.0, 1.0]), clip.sample(0.5)["a"].rotation);
+    }
+}
@@ -6,6 +6,10 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::fmt;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct AssetProfile {
     /// Specifies the target rendering API.
@@ -16,8 +20,15 @@ pub struct AssetProfile {
     #[serde(default = "asset_profile_version_default")]
     pub version: String, 
 
-    // TODO: extension
-    // TODO: extras
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
 }
 
 fn asset_profile_api_default() -> String {
@@ -47,6 +58,70 @@ pub struct Asset {
     /// The glTF version.
     pub version: String, 
 
-    // TODO: extension
-    // TODO: extras
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+impl Asset {
+    /// Returns the parsed glTF version, or `None` if it is malformed.
+    pub fn version(&self) -> Option<Version> {
+        Version::parse(&self.version)
+    }
+}
+
+/// A glTF version of the form `major.minor`.
+///
+/// A trailing patch number, as in `"1.0.3"`, is accepted and ignored.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Version {
+    /// The major version, which changes with incompatible revisions.
+    pub major: u32,
+
+    /// The minor version, which changes with backwards compatible additions.
+    pub minor: u32,
+}
+
+impl Version {
+    /// Parses a version such as `"1.0"`.
+    pub fn parse(version: &str) -> Option<Version> {
+        let mut parts = version.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        match parts.next() {
+            Some(patch) if patch.parse::<u32>().is_err() => return None,
+            _ => {},
+        }
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Version { major, minor })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_versions() {
+        assert_eq!(Some(Version { major: 1, minor: 0 }), Version::parse("1.0"));
+        assert_eq!(Some(Version { major: 1, minor: 0 }), Version::parse("1.0.3"));
+        assert_eq!(None, Version::parse("1"));
+        assert_eq!(None, Version::parse("1.x"));
+        assert_eq!(None, Version::parse("1.0.3.4"));
+        assert_eq!("2.1", Version { major: 2, minor: 1 }.to_string());
+    }
 }
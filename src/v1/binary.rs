@@ -0,0 +1,203 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The binary glTF 1.0 container defined by the `KHR_binary_glTF` extension.
+
+use serde_json;
+use std::error::Error as StdError;
+use std::fmt;
+use v1::extensions::Extensions;
+
+/// The name of the extension.
+pub const EXTENSION: &str = "KHR_binary_glTF";
+
+/// The ID of the buffer referring to the binary body of the container.
+pub const BUFFER_ID: &str = "binary_glTF";
+
+/// The magic bytes at the start of every binary glTF file.
+pub const MAGIC: [u8; 4] = *b"glTF";
+
+/// The length of the header in bytes.
+pub const HEADER_LENGTH: usize = 20;
+
+/// The only version of the container defined by the extension.
+pub const VERSION: u32 = 1;
+
+/// The only content format defined by the extension, i.e. JSON.
+pub const CONTENT_FORMAT_JSON: u32 = 0;
+
+/// Problems with the layout of a binary glTF container.
+#[derive(Debug)]
+pub enum Error {
+    /// The data does not start with the magic bytes `glTF`.
+    Magic,
+    /// The container version is not 1.
+    Version(u32),
+    /// The content format is not JSON.
+    ContentFormat(u32),
+    /// The lengths in the header do not fit the data.
+    Length,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Magic => f.write_str("missing glTF magic bytes"),
+            Error::Version(version) => write!(f, "unsupported version {}", version),
+            Error::ContentFormat(format) => {
+                write!(f, "unsupported content format {}", format)
+            },
+            Error::Length => f.write_str("lengths in the header exceed the data"),
+        }
+    }
+}
+
+impl StdError for Error {}
+
+/// The header of a binary glTF container.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Header {
+    /// The container version.
+    pub version: u32,
+
+    /// The length of the whole container in bytes, including the header.
+    pub length: u32,
+
+    /// The length of the JSON content in bytes.
+    pub content_length: u32,
+
+    /// The format of the content.
+    pub content_format: u32,
+}
+
+/// A binary glTF container split into its parts.
+#[derive(Clone, Copy, Debug)]
+pub struct Glb<'a> {
+    /// The container header.
+    pub header: Header,
+
+    /// The JSON content.
+    pub content: &'a [u8],
+
+    /// The binary body, referred to by the `binary_glTF` buffer.
+    pub body: &'a [u8],
+}
+
+impl<'a> Glb<'a> {
+    /// Splits a binary glTF container into its header, content, and body.
+    pub fn from_slice(data: &'a [u8]) -> Result<Glb<'a>, Error> {
+        if !is_binary(data) {
+            return Err(Error::Magic);
+        }
+        if data.len() < HEADER_LENGTH {
+            return Err(Error::Length);
+        }
+        let header = Header {
+            version: read_u32(&data[4..8]),
+            length: read_u32(&data[8..12]),
+            content_length: read_u32(&data[12..16]),
+            content_format: read_u32(&data[16..20]),
+        };
+        if header.version != VERSION {
+            return Err(Error::Version(header.version));
+        }
+        if header.content_format != CONTENT_FORMAT_JSON {
+            return Err(Error::ContentFormat(header.content_format));
+        }
+        let length = header.length as usize;
+        let content_end = HEADER_LENGTH + header.content_length as usize;
+        if length > data.len() || content_end > length {
+            return Err(Error::Length);
+        }
+        Ok(Glb {
+            header,
+            content: &data[HEADER_LENGTH..content_end],
+            body: &data[content_end..length],
+        })
+    }
+}
+
+/// The `KHR_binary_glTF` extension object of an image.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Image {
+    /// The ID of the bufferView holding the encoded image.
+    #[serde(rename = "bufferView")]
+    pub buffer_view: String,
+
+    /// The MIME type of the image, e.g. `"image/png"`.
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+
+    /// The width of the image in pixels.
+    pub width: u32,
+
+    /// The height of the image in pixels.
+    pub height: u32,
+}
+
+/// The `KHR_binary_glTF` extension object of a shader.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Shader {
+    /// The ID of the bufferView holding the GLSL source.
+    #[serde(rename = "bufferView")]
+    pub buffer_view: String,
+}
+
+/// Returns `true` if the data starts with the binary glTF magic bytes.
+pub fn is_binary(data: &[u8]) -> bool {
+    data.starts_with(&MAGIC)
+}
+
+/// Deserializes the `KHR_binary_glTF` object of an extensions dictionary.
+pub(crate) fn extension<T>(extensions: &Extensions) -> Option<T>
+    where T: ::serde::Deserialize
+{
+    let value = extensions.get(EXTENSION)?;
+    serde_json::from_value(value.clone()).ok()
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn glb(content: &[u8], body: &[u8]) -> Vec<u8> {
+        let length = HEADER_LENGTH + content.len() + body.len();
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&VERSION.to_le_bytes());
+        data.extend_from_slice(&(length as u32).to_le_bytes());
+        data.extend_from_slice(&(content.len() as u32).to_le_bytes());
+        data.extend_from_slice(&CONTENT_FORMAT_JSON.to_le_bytes());
+        data.extend_from_slice(content);
+        data.extend_from_slice(body);
+        data
+    }
+
+    #[test]
+    fn it_splits_the_container() {
+        let data = glb(b"{}", &[1, 2, 3]);
+        let glb = Glb::from_slice(&data).unwrap();
+        assert_eq!(b"{}", glb.content);
+        assert_eq!(&[1, 2, 3], glb.body);
+        assert_eq!(data.len() as u32, glb.header.length);
+    }
+
+    #[test]
+    fn it_rejects_malformed_containers() {
+        assert!(matches!(Glb::from_slice(b"{}"), Err(Error::Magic)));
+        let mut data = glb(b"{}", &[]);
+        data.truncate(HEADER_LENGTH);
+        assert!(matches!(Glb::from_slice(&data), Err(Error::Length)));
+        let mut data = glb(b"{}", &[]);
+        data[4] = 2;
+        assert!(matches!(Glb::from_slice(&data), Err(Error::Version(2))));
+    }
+}
@@ -6,6 +6,17 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::collections::HashMap;
+use std::sync::Arc;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+
+/// The contents of buffers, keyed by buffer ID.
+///
+/// The data is reference counted, so assets importing the same file through
+/// a `FileCache` share its storage.
+pub type Buffers = HashMap<String, Arc<[u8]>>;
+
 enum_number! {
     Target {
         ArrayBuffer = 34962,
@@ -36,8 +47,15 @@ pub struct Buffer {
     /// have the same name, or two buffers could even have the same name.
     pub name: Option<String>,
 
-    // TODO: extension
-    // TODO: extras
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -67,6 +85,27 @@ pub struct BufferView {
     /// have the same name, or two bufferViews could even have the same name.
     pub name: Option<String>,
 
-    // TODO: extension
-    // TODO: extras
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+impl BufferView {
+    /// Returns the bytes of the buffer covered by this view, or `None` if the
+    /// buffer is not loaded or too short.
+    pub fn data<'a>(&self, buffers: &'a Buffers) -> Option<&'a [u8]> {
+        let buffer = buffers.get(&self.buffer)?;
+        buffer.get(self.byte_offset..self.byte_offset.checked_add(self.byte_length)?)
+    }
 }
+
+impl_references!(BufferView {
+    ids { buffer: "buffer" => buffers, }
+    nested {}
+});
@@ -0,0 +1,291 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Creation of accessors backed by new in-memory buffer data.
+
+use std::sync::Arc;
+use v1::Gltf;
+use v1::accessor::{Accessor, ComponentType, Kind, Values};
+use v1::buffer::{Buffer, BufferView, Buffers, Target};
+use v1::mesh;
+
+/// A type whose values can be written as accessor elements.
+pub trait Writable: Copy {
+    /// The datatype of each component.
+    const COMPONENT_TYPE: ComponentType;
+
+    /// The number of components in each element.
+    const KIND: Kind;
+
+    /// Appends the little-endian bytes of the element.
+    fn write(&self, bytes: &mut Vec<u8>);
+}
+
+macro_rules! impl_writable {
+    ([$ty:ty; $n:expr], $component_type:ident, $kind:ident) => {
+        impl Writable for [$ty; $n] {
+            const COMPONENT_TYPE: ComponentType = ComponentType::$component_type;
+            const KIND: Kind = Kind::$kind;
+
+            fn write(&self, bytes: &mut Vec<u8>) {
+                for component in self {
+                    bytes.extend_from_slice(&component.to_le_bytes());
+                }
+            }
+        }
+    };
+    ($ty:ty, $component_type:ident, $kind:ident) => {
+        impl Writable for $ty {
+            const COMPONENT_TYPE: ComponentType = ComponentType::$component_type;
+            const KIND: Kind = Kind::$kind;
+
+            fn write(&self, bytes: &mut Vec<u8>) {
+                bytes.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+    };
+}
+
+impl_writable!(f32, F32, Scalar);
+impl_writable!([f32; 2], F32, Vec2);
+impl_writable!([f32; 3], F32, Vec3);
+impl_writable!([f32; 4], F32, Vec4);
+impl_writable!([f32; 16], F32, Mat4);
+impl_writable!(u16, U16, Scalar);
+impl_writable!(u32, U32, Scalar);
+impl_writable!([u8; 4], U8, Vec4);
+impl_writable!([u16; 4], U16, Vec4);
+
+/// Appends data to a buffer, describing it with new bufferViews and
+/// accessors.
+///
+/// Each accessor gets a bufferView of its own, aligned to four bytes so that
+/// every component type is correctly aligned. Since buffer data is shared,
+/// the appended bytes are collected by the builder and the buffer is replaced
+/// by a copy including them when the builder is dropped.
+#[derive(Debug)]
+pub struct Builder<'a> {
+    gltf: &'a mut Gltf,
+    buffers: &'a mut Buffers,
+    buffer: String,
+    /// The bytes appended so far.
+    appended: Vec<u8>,
+}
+
+impl<'a> Builder<'a> {
+    /// Creates a builder appending to the buffer with the given ID.
+    ///
+    /// The buffer is created with the URI `<id>.bin` if the asset does not
+    /// contain it yet.
+    pub fn new(gltf: &'a mut Gltf, buffers: &'a mut Buffers, buffer: &str) -> Self {
+        gltf.buffers.entry(buffer.to_string()).or_insert_with(|| Buffer {
+            uri: format!("{}.bin", buffer),
+            ..Default::default()
+        });
+        buffers.entry(buffer.to_string()).or_default();
+        Builder {
+            gltf,
+            buffers,
+            buffer: buffer.to_string(),
+            appended: Vec::new(),
+        }
+    }
+
+    /// Returns the asset being built.
+    pub fn gltf(&mut self) -> &mut Gltf {
+        self.gltf
+    }
+
+    /// Appends elements as a new tightly packed accessor and returns its ID.
+    ///
+    /// The `min` and `max` of the accessor are computed from the elements. The
+    /// ID is `id`, or `id` with a numeric suffix if `id` is already in
+    /// use. `target` is the target of the new bufferView.
+    pub fn accessor<T: Writable>(
+        &mut self,
+        id: &str,
+        elements: &[T],
+        target: Option<Target>,
+    ) -> String {
+        let mut data = Vec::new();
+        for element in elements {
+            element.write(&mut data);
+        }
+        self.accessor_bytes(id, T::COMPONENT_TYPE, T::KIND, &data, target)
+    }
+
+    /// Appends vertex indices as a new accessor and returns its ID, as
+    /// `accessor` does.
+    ///
+    /// The indices are stored as `u16` when they all fit, and as `u32`
+    /// otherwise, in which case `OES_element_index_uint` is added to the
+    /// extensions used by the asset.
+    pub fn indices(&mut self, id: &str, indices: &[u32]) -> String {
+        let target = Some(Target::ElementArrayBuffer);
+        if let Some(narrow) = mesh::narrow_indices(indices) {
+            return self.accessor(id, &narrow, target);
+        }
+        let extensions = &mut self.gltf.extensions_used;
+        if !extensions.iter().any(|name| name == mesh::ELEMENT_INDEX_UINT) {
+            extensions.push(mesh::ELEMENT_INDEX_UINT.to_string());
+        }
+        self.accessor(id, indices, target)
+    }
+
+    /// Appends components whose datatype is only known at runtime as a new
+    /// accessor of the given kind and returns its ID, as `accessor` does for
+    /// typed elements.
+    pub fn accessor_values(
+        &mut self,
+        id: &str,
+        kind: Kind,
+        values: &Values,
+        target: Option<Target>,
+    ) -> String {
+        let bytes = values.to_le_bytes();
+        self.accessor_bytes(id, values.component_type(), kind, &bytes, target)
+    }
+
+    /// Appends tightly packed little-endian element data as a new accessor
+    /// and returns its ID, as `accessor` does for typed elements.
+    ///
+    /// The element count is derived from the length of `bytes`.
+    pub fn accessor_bytes(
+        &mut self,
+        id: &str,
+        component_type: ComponentType,
+        kind: Kind,
+        bytes: &[u8],
+        target: Option<Target>,
+    ) -> String {
+        let id = unique_id(self.gltf, id);
+        let view_id = self.buffer_view(&format!("{}_view", id), bytes, target);
+        let element_size = component_type.size() * kind.components();
+        let mut accessor = Accessor {
+            buffer_view: view_id,
+            byte_offset: 0,
+            byte_stride: 0,
+            component_type,
+            count: (bytes.len() / element_size) as u32,
+            kind,
+            ..Default::default()
+        };
+        if let Some((min, max)) = accessor.view_of(bytes).bounds() {
+            accessor.min = Some(min);
+            accessor.max = Some(max);
+        }
+        self.gltf.accessors.insert(id.clone(), accessor);
+        id
+    }
+
+    /// Appends raw bytes as a new bufferView and returns its ID.
+    ///
+    /// The ID is `id`, or `id` with a numeric suffix if `id` is already in
+    /// use. The bufferView starts at a multiple of four bytes.
+    pub fn buffer_view(
+        &mut self,
+        id: &str,
+        bytes: &[u8],
+        target: Option<Target>,
+    ) -> String {
+        let id = unique_id(self.gltf, id);
+        let start = self.buffers[&self.buffer].len();
+        while !(start + self.appended.len()).is_multiple_of(4) {
+            self.appended.push(0);
+        }
+        let byte_offset = start + self.appended.len();
+        self.appended.extend_from_slice(bytes);
+        let byte_length = start + self.appended.len();
+        self.gltf.buffers.get_mut(&self.buffer).unwrap().byte_length = byte_length;
+        self.gltf.buffer_views.insert(id.clone(), BufferView {
+            buffer: self.buffer.clone(),
+            byte_offset,
+            byte_length: bytes.len(),
+            target,
+            ..Default::default()
+        });
+        id
+    }
+}
+
+impl<'a> Drop for Builder<'a> {
+    fn drop(&mut self) {
+        if self.appended.is_empty() {
+            return;
+        }
+        let data = self.buffers.get_mut(&self.buffer).unwrap();
+        *data = data.iter().chain(&self.appended).cloned().collect::<Arc<[u8]>>();
+    }
+}
+
+/// Returns `prefix`, or `prefix` with the smallest numeric suffix that makes
+/// it unused by any accessor or bufferView.
+pub(crate) fn unique_id(gltf: &Gltf, prefix: &str) -> String {
+    unused(prefix, |id| {
+        gltf.accessors.contains_key(id) || gltf.buffer_views.contains_key(id)
+    })
+}
+
+/// Returns `prefix`, or `prefix` with the smallest numeric suffix that makes
+/// it unused by any buffer.
+pub(crate) fn unique_buffer_id(gltf: &Gltf, buffers: &Buffers, prefix: &str) -> String {
+    unused(prefix, |id| gltf.buffers.contains_key(id) || buffers.contains_key(id))
+}
+
+fn unused<F: Fn(&str) -> bool>(prefix: &str, used: F) -> String {
+    if !used(prefix) {
+        return prefix.to_string();
+    }
+    (1..)
+        .map(|n| format!("{}_{}", prefix, n))
+        .find(|id| !used(id))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_appends_aligned_accessors() {
+        let mut gltf = Gltf::default();
+        let mut buffers = Buffers::new();
+        let (indices, positions) = {
+            let mut builder = Builder::new(&mut gltf, &mut buffers, "geometry");
+            let indices = builder.accessor("data", &[0u16, 1, 2],
+                                           Some(Target::ElementArrayBuffer));
+            let positions = builder.accessor("data", &[[1.0f32, 2.0, 3.0]], None);
+            (indices, positions)
+        };
+        assert_eq!(("data", "data_1"), (indices.as_str(), positions.as_str()));
+        assert_eq!(20, gltf.buffers["geometry"].byte_length);
+        assert_eq!(20, buffers["geometry"].len());
+        let view = &gltf.buffer_views[&gltf.accessors["data_1"].buffer_view];
+        assert_eq!((8, 12), (view.byte_offset, view.byte_length));
+
+        let accessor = &gltf.accessors["data_1"];
+        let read: Vec<[f32; 3]> = accessor.view(&gltf, &buffers).unwrap().iter().collect();
+        assert_eq!(vec![[1.0, 2.0, 3.0]], read);
+        assert_eq!(Some(vec![1.0, 2.0, 3.0]), accessor.min);
+        assert_eq!(Some(vec![0.0]), gltf.accessors["data"].min);
+        assert_eq!(Some(vec![2.0]), gltf.accessors["data"].max);
+    }
+
+    #[test]
+    fn it_declares_wide_indices() {
+        let mut gltf = Gltf::default();
+        let mut buffers = Buffers::new();
+        let (narrow, wide) = {
+            let mut builder = Builder::new(&mut gltf, &mut buffers, "geometry");
+            (builder.indices("narrow", &[0, 1, 2]), builder.indices("wide", &[0, 1, 70000]))
+        };
+        assert_eq!(ComponentType::U16, gltf.accessors[&narrow].component_type);
+        assert_eq!(ComponentType::U32, gltf.accessors[&wide].component_type);
+        assert_eq!(vec![mesh::ELEMENT_INDEX_UINT], gltf.extensions_used);
+    }
+}
@@ -6,6 +6,11 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use v1::Gltf;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+use v1::validation::{report, report_value, Code, Error, Pointer, Validate};
+
 enum_string! {
     CameraType {
         Orthographic = "orthographic",
@@ -42,8 +47,15 @@ pub struct Perspective {
     #[serde(rename = "znear")]
     pub z_near: f32,
 
-    // TODO: extension
-    // TODO: extras
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -64,8 +76,15 @@ pub struct Orthographic {
     #[serde(rename = "znear")]
     pub z_near: f32,
 
-    // TODO: extension
-    // TODO: extras
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -92,6 +111,113 @@ pub struct Camera {
     /// the same name, or two cameras could even have the same name.
     pub name: Option<String>,
 
-    // TODO: extension
-    // TODO: extras
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+impl Validate for Camera {
+    fn validate(&self, _gltf: &Gltf, pointer: &Pointer, errors: &mut Vec<Error>) {
+        match self.kind {
+            CameraType::Perspective => match self.perspective {
+                Some(ref perspective) => {
+                    perspective.validate_ranges(&pointer.field("perspective"), errors);
+                },
+                None => report(errors, Code::MissingProperty, pointer.clone(),
+                               "perspective camera must define perspective".to_string()),
+            },
+            CameraType::Orthographic => match self.orthographic {
+                Some(ref orthographic) => {
+                    orthographic.validate_ranges(&pointer.field("orthographic"), errors);
+                },
+                None => report(errors, Code::MissingProperty, pointer.clone(),
+                               "orthographic camera must define orthographic".to_string()),
+            },
+        }
+    }
+}
+
+impl Perspective {
+    fn validate_ranges(&self, pointer: &Pointer, errors: &mut Vec<Error>) {
+        if let Some(aspect_ratio) = self.aspect_ratio {
+            if aspect_ratio <= 0.0 {
+                report_value(errors, Code::OutOfRange, pointer.field("aspectRatio"),
+                             aspect_ratio, "aspectRatio must be positive".to_string());
+            }
+        }
+        if self.y_fov <= 0.0 {
+            report_value(errors, Code::OutOfRange, pointer.field("yfov"), self.y_fov,
+                         "yfov must be positive".to_string());
+        }
+        if self.z_near <= 0.0 {
+            report_value(errors, Code::OutOfRange, pointer.field("znear"), self.z_near,
+                         "znear must be positive".to_string());
+        }
+        if self.z_far <= self.z_near {
+            report_value(errors, Code::OutOfRange, pointer.field("zfar"), self.z_far,
+                         format!("zfar must be greater than znear ({})", self.z_near));
+        }
+    }
+}
+
+impl Orthographic {
+    fn validate_ranges(&self, pointer: &Pointer, errors: &mut Vec<Error>) {
+        if self.z_near < 0.0 {
+            report_value(errors, Code::OutOfRange, pointer.field("znear"), self.z_near,
+                         "znear must not be negative".to_string());
+        }
+        if self.z_far <= self.z_near {
+            report_value(errors, Code::OutOfRange, pointer.field("zfar"), self.z_far,
+                         format!("zfar must be greater than znear ({})", self.z_near));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_validates_clipping_planes() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "cameras": {
+                "ok": {
+                    "type": "perspective",
+                    "perspective": { "yfov": 0.8, "znear": 0.1, "zfar": 100.0 }
+                },
+                "inverted": {
+                    "type": "perspective",
+                    "perspective": { "yfov": 0.8, "znear": 0.0, "zfar": -1.0 }
+                },
+                "flat": {
+                    "type": "orthographic",
+                    "orthographic": { "xmag": 1.0, "ymag": 1.0, "znear": 1.0, "zfar": 1.0 }
+                }
+            }
+        }"#).unwrap();
+        let mut pointers: Vec<String> = gltf.validate()
+            .into_iter()
+            .map(|error| error.pointer.to_string())
+            .collect();
+        pointers.sort();
+        assert_eq!(vec![
+            "/cameras/flat/orthographic/zfar",
+            "/cameras/inverted/perspective/zfar",
+            "/cameras/inverted/perspective/znear",
+        ], pointers);
+        let znear = gltf.validate()
+            .into_iter()
+            .find(|error| error.pointer.as_str() == "/cameras/inverted/perspective/znear")
+            .unwrap();
+        assert_eq!(Code::OutOfRange, znear.code);
+        assert_eq!(Some(serde_json::Value::from(0.0)), znear.value);
+        assert_eq!(None, gltf.cameras["ok"].perspective.as_ref().unwrap().aspect_ratio);
+    }
 }
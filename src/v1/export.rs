@@ -0,0 +1,109 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Writers of flattened scene geometry in simple interchange formats, for
+//! inspecting imported geometry in other tools.
+
+use std::io::{self, Write};
+use v1::scene::Flattened;
+
+/// Writes geometry as a Wavefront OBJ file.
+///
+/// Normals are written when there is one per vertex.
+pub fn write_obj<W: Write>(geometry: &Flattened, mut writer: W) -> io::Result<()> {
+    let has_normals = geometry.normals.len() == geometry.positions.len();
+    for p in &geometry.positions {
+        writeln!(writer, "v {} {} {}", p[0], p[1], p[2])?;
+    }
+    if has_normals {
+        for n in &geometry.normals {
+            writeln!(writer, "vn {} {} {}", n[0], n[1], n[2])?;
+        }
+    }
+    for triangle in geometry.indices.chunks(3).filter(|triangle| triangle.len() == 3) {
+        // OBJ indices start from 1.
+        let [a, b, c] = [triangle[0] + 1, triangle[1] + 1, triangle[2] + 1];
+        if has_normals {
+            writeln!(writer, "f {0}//{0} {1}//{1} {2}//{2}", a, b, c)?;
+        } else {
+            writeln!(writer, "f {} {} {}", a, b, c)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes geometry as an ASCII PLY file.
+///
+/// Normals are written when there is one per vertex.
+pub fn write_ply<W: Write>(geometry: &Flattened, mut writer: W) -> io::Result<()> {
+    let has_normals = geometry.normals.len() == geometry.positions.len();
+    let triangles: Vec<_> = geometry.indices.chunks(3)
+        .filter(|triangle| triangle.len() == 3)
+        .collect();
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format ascii 1.0")?;
+    writeln!(writer, "element vertex {}", geometry.positions.len())?;
+    for property in &["x", "y", "z"] {
+        writeln!(writer, "property float {}", property)?;
+    }
+    if has_normals {
+        for property in &["nx", "ny", "nz"] {
+            writeln!(writer, "property float {}", property)?;
+        }
+    }
+    writeln!(writer, "element face {}", triangles.len())?;
+    writeln!(writer, "property list uchar uint vertex_indices")?;
+    writeln!(writer, "end_header")?;
+    for (i, p) in geometry.positions.iter().enumerate() {
+        if has_normals {
+            let n = geometry.normals[i];
+            writeln!(writer, "{} {} {} {} {} {}", p[0], p[1], p[2], n[0], n[1], n[2])?;
+        } else {
+            writeln!(writer, "{} {} {}", p[0], p[1], p[2])?;
+        }
+    }
+    for triangle in triangles {
+        writeln!(writer, "3 {} {} {}", triangle[0], triangle[1], triangle[2])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn triangle() -> Flattened {
+        Flattened {
+            positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.5, 0.0]],
+            normals: vec![[0.0, 0.0, 1.0]; 3],
+            indices: vec![0, 1, 2],
+        }
+    }
+
+    #[test]
+    fn it_writes_obj() {
+        let mut obj = Vec::new();
+        write_obj(&triangle(), &mut obj).unwrap();
+        assert_eq!("v 0 0 0\nv 1 0 0\nv 0 1.5 0\n\
+                    vn 0 0 1\nvn 0 0 1\nvn 0 0 1\n\
+                    f 1//1 2//2 3//3\n",
+                   String::from_utf8(obj).unwrap());
+    }
+
+    #[test]
+    fn it_writes_ply() {
+        let mut ply = Vec::new();
+        write_ply(&triangle(), &mut ply).unwrap();
+        let ply = String::from_utf8(ply).unwrap();
+        assert!(ply.starts_with("ply\nformat ascii 1.0\nelement vertex 3\n"));
+        assert!(ply.contains("element face 1\n"));
+        assert!(ply.ends_with("end_header\n\
+                               0 0 0 0 0 1\n1 0 0 0 0 1\n0 1.5 0 0 0 1\n\
+                               3 0 1 2\n"));
+    }
+}
@@ -0,0 +1,48 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::collections::hash_map;
+
+/// Dictionary object with extension-specific objects, keyed by extension name.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Extensions(HashMap<String, Value>);
+
+impl Extensions {
+    /// Returns the object of the named extension, e.g. `"KHR_binary_glTF"`.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    /// Returns `true` if the named extension is present.
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    /// Returns the number of extensions present.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no extensions are present.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the extension names and their objects.
+    pub fn iter(&self) -> hash_map::Iter<'_, String, Value> {
+        self.0.iter()
+    }
+
+    /// Inserts an extension object, returning the previous object of the same
+    /// name, if any.
+    pub fn insert(&mut self, name: String, value: Value) -> Option<Value> {
+        self.0.insert(name, value)
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use serde::Deserialize;
+use serde_json;
+use serde_json::Value;
+use std::collections::HashMap;
+use v1::Gltf;
+use v1::validation::Pointer;
+
+/// Application-specific data.
+///
+/// The data is kept as untyped JSON and can be converted into any type
+/// implementing `Deserialize` on demand with `decode`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Extras(Value);
+
+impl Default for Extras {
+    fn default() -> Extras {
+        Extras(Value::Null)
+    }
+}
+
+impl Extras {
+    /// Returns the untyped JSON value.
+    pub fn value(&self) -> &Value {
+        &self.0
+    }
+
+    /// Returns `true` if no extras were provided.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_null()
+    }
+
+    /// Deserializes the extras into a user-defined type.
+    pub fn decode<T: Deserialize>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(self.0.clone())
+    }
+}
+
+/// Calls `f` with the extras of every object that has any, as described by
+/// `Gltf::visit_extras`.
+pub(crate) fn visit(gltf: &Gltf, f: &mut dyn FnMut(&Pointer, &Extras)) {
+    let mut visit = |pointer: &Pointer, extras: &Extras| {
+        if !extras.is_empty() {
+            f(pointer, extras);
+        }
+    };
+    let root = Pointer::root();
+    visit(&root, &gltf.extras);
+    let pointer = root.field("asset");
+    visit(&pointer, &gltf.asset.extras);
+    visit(&pointer.field("profile"), &gltf.asset.profile.extras);
+
+    for (pointer, accessor) in sorted(&root, "accessors", &gltf.accessors) {
+        visit(&pointer, &accessor.extras);
+    }
+    for (pointer, animation) in sorted(&root, "animations", &gltf.animations) {
+        visit(&pointer, &animation.extras);
+        for (i, channel) in animation.channels.iter().enumerate() {
+            let pointer = pointer.field("channels").index(i);
+            visit(&pointer, &channel.extras);
+            visit(&pointer.field("target"), &channel.target.extras);
+        }
+        for (pointer, sampler) in sorted(&pointer, "samplers", &animation.samplers) {
+            visit(&pointer, &sampler.extras);
+        }
+    }
+    for (pointer, buffer) in sorted(&root, "buffers", &gltf.buffers) {
+        visit(&pointer, &buffer.extras);
+    }
+    for (pointer, buffer_view) in sorted(&root, "bufferViews", &gltf.buffer_views) {
+        visit(&pointer, &buffer_view.extras);
+    }
+    for (pointer, camera) in sorted(&root, "cameras", &gltf.cameras) {
+        visit(&pointer, &camera.extras);
+        if let Some(ref orthographic) = camera.orthographic {
+            visit(&pointer.field("orthographic"), &orthographic.extras);
+        }
+        if let Some(ref perspective) = camera.perspective {
+            visit(&pointer.field("perspective"), &perspective.extras);
+        }
+    }
+    for (pointer, image) in sorted(&root, "images", &gltf.images) {
+        visit(&pointer, &image.extras);
+    }
+    for (pointer, material) in sorted(&root, "materials", &gltf.materials) {
+        visit(&pointer, &material.extras);
+    }
+    for (pointer, mesh) in sorted(&root, "meshes", &gltf.meshes) {
+        visit(&pointer, &mesh.extras);
+        for (i, primitive) in mesh.primitives.iter().enumerate() {
+            visit(&pointer.field("primitives").index(i), &primitive.extras);
+        }
+    }
+    for (pointer, node) in sorted(&root, "nodes", &gltf.nodes) {
+        visit(&pointer, &node.extras);
+    }
+    for (pointer, program) in sorted(&root, "programs", &gltf.programs) {
+        visit(&pointer, &program.extras);
+    }
+    for (pointer, sampler) in sorted(&root, "samplers", &gltf.samplers) {
+        visit(&pointer, &sampler.extras);
+    }
+    for (pointer, scene) in sorted(&root, "scenes", &gltf.scenes) {
+        visit(&pointer, &scene.extras);
+    }
+    for (pointer, shader) in sorted(&root, "shaders", &gltf.shaders) {
+        visit(&pointer, &shader.extras);
+    }
+    for (pointer, skin) in sorted(&root, "skins", &gltf.skins) {
+        visit(&pointer, &skin.extras);
+    }
+    for (pointer, technique) in sorted(&root, "techniques", &gltf.techniques) {
+        visit(&pointer, &technique.extras);
+        for (pointer, parameter) in sorted(&pointer, "parameters", &technique.parameters) {
+            visit(&pointer, &parameter.extras);
+        }
+        if let Some(ref states) = technique.states {
+            let pointer = pointer.field("states");
+            visit(&pointer, &states.extras);
+            if let Some(ref functions) = states.functions {
+                visit(&pointer.field("functions"), &functions.extras);
+            }
+        }
+    }
+    for (pointer, texture) in sorted(&root, "textures", &gltf.textures) {
+        visit(&pointer, &texture.extras);
+    }
+}
+
+/// Returns the pointers to the members of a dictionary object and the members
+/// themselves, sorted by ID.
+fn sorted<'a, T>(
+    parent: &Pointer,
+    name: &str,
+    map: &'a HashMap<String, T>,
+) -> Vec<(Pointer, &'a T)> {
+    let dictionary = parent.field(name);
+    let mut members: Vec<_> = map.iter().collect();
+    members.sort_by(|a, b| a.0.cmp(b.0));
+    members.into_iter().map(|(id, value)| (dictionary.field(id), value)).collect()
+}
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use v1::node::Node;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Metadata {
+        lod: u32,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn it_decodes_extras_on_demand() {
+        let data = r#"{
+    "name": "tree",
+    "extras": { "lod": 2, "tags": ["static"] }
+}"#;
+        let node: Node = serde_json::from_str(data).unwrap();
+        let metadata: Metadata = node.extras.decode().unwrap();
+        assert_eq!(Metadata { lod: 2, tags: vec!["static".to_string()] }, metadata);
+        assert!(node.extras.decode::<Vec<u32>>().is_err());
+
+        let node: Node = serde_json::from_str("{}").unwrap();
+        assert!(node.extras.is_empty());
+        assert!(!serde_json::to_string(&node).unwrap().contains("extras"));
+    }
+
+    #[test]
+    fn it_visits_every_extras() {
+        use v1::Gltf;
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "extras": { "pipeline": 1 },
+            "meshes": {
+                "mesh": {
+                    "primitives": [
+                        { "attributes": {}, "material": "material" },
+                        { "attributes": {}, "material": "material", "extras": 3 }
+                    ]
+                }
+            },
+            "nodes": {
+                "b": { "extras": { "lod": 1 } },
+                "a": { "extras": { "lod": 0 } },
+                "c": {}
+            }
+        }"#).unwrap();
+        let mut visited = Vec::new();
+        gltf.visit_extras(|pointer, extras| {
+            visited.push((pointer.to_string(), extras.value().clone()));
+        });
+        let pointers: Vec<_> = visited.iter().map(|v| v.0.as_str()).collect();
+        assert_eq!(vec!["", "/meshes/mesh/primitives/1", "/nodes/a", "/nodes/b"],
+                   pointers);
+        assert_eq!(Some(0), visited[2].1.get("lod").and_then(|lod| lod.as_u64()));
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions of glTF enumerations to raw OpenGL `GLenum` values.
+
+use v1::accessor::ComponentType;
+use v1::buffer;
+use v1::mesh::Mode;
+use v1::shader::ShaderType;
+use v1::texture::{self, Filter, TexelType, Wrap};
+
+/// An enumeration whose values are OpenGL constants.
+pub trait GlEnum: Copy {
+    /// Returns the `GLenum` value, e.g. `GL_FLOAT` for `ComponentType::F32`.
+    fn as_gl_enum(self) -> u32;
+}
+
+macro_rules! impl_gl_enum {
+    ($($ty:ty,)*) => {
+        $(
+            impl GlEnum for $ty {
+                fn as_gl_enum(self) -> u32 {
+                    self as u32
+                }
+            }
+        )*
+    }
+}
+
+impl_gl_enum!(
+    buffer::Target,
+    ComponentType,
+    Filter,
+    Mode,
+    ShaderType,
+    TexelType,
+    texture::Format,
+    texture::Target,
+    Wrap,
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_returns_gl_constants() {
+        assert_eq!(0x1406, ComponentType::F32.as_gl_enum());
+        assert_eq!(0x2601, Filter::Linear.as_gl_enum());
+        assert_eq!(0x812F, Wrap::ClampToEdge.as_gl_enum());
+        assert_eq!(0x0004, Mode::Triangles.as_gl_enum());
+        assert_eq!(0x8893, buffer::Target::ElementArrayBuffer.as_gl_enum());
+    }
+}
@@ -6,6 +6,21 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(feature = "image")]
+use image_crate;
+#[cfg(feature = "image")]
+use image_crate::ColorType;
+use std::collections::HashMap;
+#[cfg(feature = "image")]
+use std::io::Cursor;
+use std::sync::Arc;
+#[cfg(feature = "image")]
+use std::sync::OnceLock;
+
+use v1::binary;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Image {
     /// The uri of the image.
@@ -19,8 +34,248 @@ pub struct Image {
     ///
     /// This is not necessarily unique, e.g., an image and a buffer could have
     /// the same name, or two images could even have the same name.
-    pub name: Option<String>, 
+    pub name: Option<String>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+/// The layout of decoded pixel data.
+#[cfg(feature = "image")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// One 8-bit channel per pixel.
+    R8,
+
+    /// Two 8-bit channels per pixel.
+    R8G8,
+
+    /// Three 8-bit channels per pixel.
+    R8G8B8,
+
+    /// Four 8-bit channels per pixel.
+    R8G8B8A8,
+
+    /// One 16-bit channel per pixel.
+    R16,
+
+    /// Two 16-bit channels per pixel.
+    R16G16,
+
+    /// Three 16-bit channels per pixel.
+    R16G16B16,
+
+    /// Four 16-bit channels per pixel.
+    R16G16B16A16,
+}
+
+/// Decoded image pixels.
+#[cfg(feature = "image")]
+#[derive(Clone, Debug)]
+pub struct Pixels {
+    /// The width of the image in pixels.
+    pub width: u32,
+
+    /// The height of the image in pixels.
+    pub height: u32,
+
+    /// The layout of each pixel.
+    pub format: Format,
+
+    /// The pixel data, row by row from the top of the image.
+    ///
+    /// 16-bit channels are stored in native byte order.
+    pub data: Vec<u8>,
+}
+
+#[cfg(feature = "image")]
+impl Pixels {
+    /// Decodes a PNG or JPEG image.
+    ///
+    /// Images with floating-point channels are converted to `R8G8B8A8`.
+    pub fn decode(bytes: &[u8]) -> Result<Self, image_crate::ImageError> {
+        let image = image_crate::load_from_memory(bytes)?;
+        let (width, height) = (image.width(), image.height());
+        let (format, image) = match image.color() {
+            ColorType::L8 => (Format::R8, image),
+            ColorType::La8 => (Format::R8G8, image),
+            ColorType::Rgb8 => (Format::R8G8B8, image),
+            ColorType::Rgba8 => (Format::R8G8B8A8, image),
+            ColorType::L16 => (Format::R16, image),
+            ColorType::La16 => (Format::R16G16, image),
+            ColorType::Rgb16 => (Format::R16G16B16, image),
+            ColorType::Rgba16 => (Format::R16G16B16A16, image),
+            _ => (Format::R8G8B8A8, image.to_rgba8().into()),
+        };
+        Ok(Pixels {
+            width,
+            height,
+            format,
+            data: image.into_bytes(),
+        })
+    }
+}
+
+/// The contents of images, keyed by image ID.
+pub type Images = HashMap<String, Data>;
+
+/// The contents of an image file, decoded on first use.
+///
+/// The encoded bytes are kept so they can be uploaded directly by renderers
+/// that consume compressed textures. Decoding requires the `image` feature;
+/// without it only the encoded bytes are available.
+#[derive(Debug, Default)]
+pub struct Data {
+    encoded: Arc<[u8]>,
+    #[cfg(feature = "image")]
+    pixels: OnceLock<Pixels>,
+}
+
+impl Data {
+    /// Wraps the encoded contents of an image file, which may be shared with
+    /// other images.
+    pub fn new<T: Into<Arc<[u8]>>>(encoded: T) -> Self {
+        Data {
+            encoded: encoded.into(),
+            #[cfg(feature = "image")]
+            pixels: OnceLock::new(),
+        }
+    }
+
+    /// Returns the encoded contents of the image file.
+    pub fn encoded(&self) -> &[u8] {
+        &self.encoded
+    }
+
+    /// Returns the MIME type of the encoded image, detected from its
+    /// signature.
+    pub fn mime_type(&self) -> Option<&'static str> {
+        if self.encoded.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some("image/png")
+        } else if self.encoded.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some("image/jpeg")
+        } else if self.encoded.starts_with(b"BM") {
+            Some("image/bmp")
+        } else if self.encoded.starts_with(b"GIF8") {
+            Some("image/gif")
+        } else {
+            None
+        }
+    }
+
+    /// Returns the width and height of the image in pixels.
+    ///
+    /// Only the image header is read unless the pixels are already decoded.
+    #[cfg(feature = "image")]
+    pub fn dimensions(&self) -> Option<(u32, u32)> {
+        if let Some(pixels) = self.pixels.get() {
+            return Some((pixels.width, pixels.height));
+        }
+        image_crate::io::Reader::new(Cursor::new(&self.encoded))
+            .with_guessed_format()
+            .ok()?
+            .into_dimensions()
+            .ok()
+    }
+
+    /// Returns the decoded pixels, decoding the image on the first call.
+    #[cfg(feature = "image")]
+    pub fn pixels(&self) -> Result<&Pixels, image_crate::ImageError> {
+        if let Some(pixels) = self.pixels.get() {
+            return Ok(pixels);
+        }
+        let pixels = Pixels::decode(&self.encoded)?;
+        Ok(self.pixels.get_or_init(|| pixels))
+    }
+
+    /// Returns the encoded contents of the image file, discarding any decoded
+    /// pixels.
+    pub fn into_encoded(self) -> Arc<[u8]> {
+        self.encoded
+    }
+}
+
+impl Image {
+    /// Returns the MIME type of the image.
+    ///
+    /// The type is taken from the `KHR_binary_glTF` extension, the media type
+    /// of a data URI, or otherwise guessed from the file extension. `None` is
+    /// returned when none of these is recognized.
+    pub fn mime_type(&self) -> Option<&str> {
+        let embedded = self.extensions.get(binary::EXTENSION)
+            .and_then(|extension| extension.get("mimeType"))
+            .and_then(|mime_type| mime_type.as_str());
+        if embedded.is_some() {
+            return embedded;
+        }
+        if self.uri.starts_with("data:") {
+            let end = self.uri.find([';', ','])?;
+            return Some(&self.uri[5..end]);
+        }
+        let extension = self.uri.rsplit('.').next()?.to_lowercase();
+        match extension.as_str() {
+            "png" => Some("image/png"),
+            "jpg" | "jpeg" => Some("image/jpeg"),
+            "bmp" => Some("image/bmp"),
+            "gif" => Some("image/gif"),
+            _ => None,
+        }
+    }
+
+    /// Returns the `KHR_binary_glTF` extension object, if the image is
+    /// embedded in a binary glTF body.
+    pub fn binary(&self) -> Option<binary::Image> {
+        binary::extension(&self.extensions)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(feature = "image")]
+    use image_crate::{ImageOutputFormat, RgbImage};
+
+    #[cfg(feature = "image")]
+    fn png() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let image = RgbImage::from_pixel(3, 2, image_crate::Rgb([1, 2, 3]));
+        image_crate::DynamicImage::ImageRgb8(image)
+            .write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn it_decodes_pixels() {
+        let pixels = Pixels::decode(&png()).unwrap();
+        assert_eq!((3, 2), (pixels.width, pixels.height));
+        assert_eq!(Format::R8G8B8, pixels.format);
+        assert_eq!(&[1, 2, 3, 1, 2, 3], &pixels.data[..6]);
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn it_keeps_encoded_bytes() {
+        let data = Data::new(png());
+        assert_eq!(Some("image/png"), data.mime_type());
+        assert_eq!(Some((3, 2)), data.dimensions());
+        assert_eq!(3, data.pixels().unwrap().width);
+        assert_eq!(png(), *data.into_encoded());
+    }
 
-    // TODO: extension
-    // TODO: extras
+    #[test]
+    fn it_reports_the_mime_type() {
+        let image = |uri: &str| Image { uri: uri.to_string(), ..Default::default() };
+        assert_eq!(Some("image/png"), image("data:image/png;base64,AAAA").mime_type());
+        assert_eq!(Some("image/jpeg"), image("textures/Wood.JPG").mime_type());
+        assert_eq!(None, image("texture").mime_type());
+    }
 }
@@ -0,0 +1,725 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use base64;
+use serde_json;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::str;
+use std::sync::{Arc, Mutex};
+use v1::{accessor, binary, builder, optimize, Error, Gltf};
+use v1::buffer::Buffers;
+use v1::extras::Extras;
+use v1::image::{Data, Images};
+use v1::validation::{Pointer, Severity, Validation};
+
+/// Where the contents of relative buffer and image URIs are read from.
+///
+/// Data URIs are decoded without consulting the source.
+pub trait Source {
+    /// Reads the contents of a relative URI, e.g. `"textures/Wood.png"`.
+    fn read(&self, uri: &str) -> Result<Vec<u8>, Error>;
+
+    /// Reads the contents of a relative URI as data that may be shared with
+    /// other readers.
+    ///
+    /// The default implementation wraps the result of `read`; sources keeping
+    /// the data in memory can hand out their own copy instead.
+    fn read_shared(&self, uri: &str) -> Result<Arc<[u8]>, Error> {
+        self.read(uri).map(Arc::from)
+    }
+
+    /// Reads `len` bytes of a relative URI starting at byte `offset`.
+    ///
+    /// The default implementation reads the whole URI and copies the range
+    /// out of it; sources that can seek should read only the range.
+    fn read_range(&self, uri: &str, offset: u64, len: usize) -> Result<Vec<u8>, Error> {
+        range(uri, &self.read(uri)?, offset, len)
+    }
+}
+
+/// Reads relative URIs from files in a directory.
+///
+/// URIs are percent-decoded, so `"My%20Texture.png"` names the file
+/// `My Texture.png`, and absolute paths such as `"/etc/passwd"` are rejected
+/// with `Error::UnsupportedUri`. `..` segments are followed, so an asset can
+/// refer to files outside the directory, e.g. `"../textures/Wood.png"`;
+/// assets from untrusted sources should be loaded from memory instead. I/O
+/// errors name the path of the file that failed to be read.
+#[derive(Clone, Debug)]
+pub struct Directory(pub PathBuf);
+
+impl Directory {
+    /// Returns the path of the file a relative URI refers to.
+    fn resolve(&self, uri: &str) -> Result<PathBuf, Error> {
+        let unsupported = || Error::UnsupportedUri(uri.to_string());
+        let decoded = percent_decode(uri).ok_or_else(unsupported)?;
+        let absolute = Path::new(&decoded).components().any(|component| {
+            matches!(component, Component::RootDir | Component::Prefix(_))
+        });
+        if absolute {
+            return Err(unsupported());
+        }
+        Ok(self.0.join(decoded))
+    }
+}
+
+impl Source for Directory {
+    fn read(&self, uri: &str) -> Result<Vec<u8>, Error> {
+        read_file(&self.resolve(uri)?)
+    }
+
+    fn read_range(&self, uri: &str, offset: u64, len: usize) -> Result<Vec<u8>, Error> {
+        let path = self.resolve(uri)?;
+        let mut data = vec![0; len];
+        fs::File::open(&path)
+            .and_then(|mut file| {
+                file.seek(SeekFrom::Start(offset))?;
+                file.read_exact(&mut data)
+            })
+            .map_err(|err| file_error(&path, err))?;
+        Ok(data)
+    }
+}
+
+/// Reads a file, naming its path in the error if that fails.
+fn read_file(path: &Path) -> Result<Vec<u8>, Error> {
+    fs::read(path).map_err(|err| file_error(path, err))
+}
+
+fn file_error(path: &Path, err: io::Error) -> Error {
+    let message = format!("{}: {}", path.display(), err);
+    Error::Io(io::Error::new(err.kind(), message))
+}
+
+/// A thread-safe cache of the files read by imports, keyed by canonical path.
+///
+/// Clones share the same cache, so one cache can be handed to imports running
+/// on several threads. A file referenced by several assets is read from disk
+/// and stored once, and the returned `Buffers` and `Images` share that
+/// storage. The cache is never invalidated, so changes to a file after it is
+/// cached are not seen until `clear` is called.
+#[derive(Clone, Debug, Default)]
+pub struct FileCache {
+    files: Arc<Mutex<HashMap<PathBuf, Arc<[u8]>>>>,
+}
+
+impl FileCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns a source reading relative URIs from files in the given
+    /// directory through this cache.
+    pub fn directory<P: Into<PathBuf>>(&self, path: P) -> CachedDirectory {
+        CachedDirectory {
+            directory: Directory(path.into()),
+            cache: self.clone(),
+        }
+    }
+
+    /// Returns the number of cached files.
+    pub fn len(&self) -> usize {
+        self.files.lock().unwrap().len()
+    }
+
+    /// Returns `true` if no files are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Discards every cached file.
+    pub fn clear(&self) {
+        self.files.lock().unwrap().clear();
+    }
+}
+
+/// Reads relative URIs from files in a directory through a `FileCache`.
+#[derive(Clone, Debug)]
+pub struct CachedDirectory {
+    directory: Directory,
+    cache: FileCache,
+}
+
+impl Source for CachedDirectory {
+    fn read(&self, uri: &str) -> Result<Vec<u8>, Error> {
+        self.read_shared(uri).map(|data| data.to_vec())
+    }
+
+    fn read_shared(&self, uri: &str) -> Result<Arc<[u8]>, Error> {
+        let path = self.directory.resolve(uri)?;
+        let path = fs::canonicalize(&path).unwrap_or(path);
+        if let Some(data) = self.cache.files.lock().unwrap().get(&path) {
+            return Ok(data.clone());
+        }
+        // The lock is not held while reading, so a file requested by several
+        // threads at once may be read more than once, but is stored once.
+        let data = Arc::from(self.directory.read(uri)?);
+        let mut files = self.cache.files.lock().unwrap();
+        Ok(files.entry(path).or_insert(data).clone())
+    }
+
+    fn read_range(&self, uri: &str, offset: u64, len: usize) -> Result<Vec<u8>, Error> {
+        range(uri, &self.read_shared(uri)?, offset, len)
+    }
+}
+
+/// Decodes the `%XX` escapes of a URI, or returns `None` if an escape is
+/// malformed or the result is not UTF-8.
+fn percent_decode(uri: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(uri.len());
+    let mut rest = uri.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail.get(..2).filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+            bytes.push(u8::from_str_radix(str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Reads relative URIs from memory, keyed by URI.
+impl Source for HashMap<String, Vec<u8>> {
+    fn read(&self, uri: &str) -> Result<Vec<u8>, Error> {
+        self.get(uri).cloned().ok_or_else(|| {
+            let message = format!("no data for {}", uri);
+            Error::Io(io::Error::new(io::ErrorKind::NotFound, message))
+        })
+    }
+
+    fn read_range(&self, uri: &str, offset: u64, len: usize) -> Result<Vec<u8>, Error> {
+        let data = self.get(uri).ok_or_else(|| {
+            let message = format!("no data for {}", uri);
+            Error::Io(io::Error::new(io::ErrorKind::NotFound, message))
+        })?;
+        range(uri, data, offset, len)
+    }
+}
+
+/// Copies `len` bytes starting at byte `offset` out of the contents of a URI.
+fn range(uri: &str, data: &[u8], offset: u64, len: usize) -> Result<Vec<u8>, Error> {
+    let start = offset as usize;
+    start.checked_add(len)
+        .and_then(|end| data.get(start..end))
+        .map(|range| range.to_vec())
+        .ok_or_else(|| {
+            let message = format!("{}: {} bytes at offset {} lie past the end",
+                                  uri, len, offset);
+            Error::Io(io::Error::new(io::ErrorKind::UnexpectedEof, message))
+        })
+}
+
+/// Options for `import_with` and `import_slice`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Options {
+    /// How thoroughly the asset is validated before its data is loaded.
+    pub validation: Validation,
+
+    /// Whether to recompute the `min` and `max` of every accessor from the
+    /// loaded data, repairing bounds that are missing or wrong.
+    pub repair_bounds: bool,
+
+    /// Whether to move the data of interleaved accessors to tightly packed
+    /// per-accessor bufferViews, for engines that require one attribute per
+    /// vertex buffer.
+    ///
+    /// The packed data is stored in a new buffer, and the accessors are
+    /// patched to reference it. The buffer's ID is `DEINTERLEAVED_BUFFER`, or
+    /// that ID with the smallest numeric suffix not used by another buffer,
+    /// e.g. `deinterleaved_1`, so existing buffers are never modified.
+    pub deinterleave: bool,
+
+    /// Whether to verify the data of buffers and images against the SHA-256
+    /// checksums they declare, failing with `Error::Checksum` on a mismatch.
+    ///
+    /// A checksum is declared as a hexadecimal string in the `sha256` property
+    /// of an object's extras, e.g. `"extras": { "sha256": "9f86d0..." }`, and
+    /// can be computed with `sha256`. Objects without one are not verified.
+    pub verify_checksums: bool,
+}
+
+/// The preferred ID of the buffer holding data moved by
+/// `Options::deinterleave`.
+pub const DEINTERLEAVED_BUFFER: &str = "deinterleaved";
+
+/// Loads a glTF 1.0 asset together with the contents of its buffers and
+/// images.
+///
+/// The asset is fully validated first, and must declare a 1.x version when it
+/// declares one at all. Buffer and image URIs may be data URIs
+/// or paths relative to the directory containing the asset. Binary glTF
+/// containers are supported, with the body loaded as the `binary_glTF` buffer
+/// and embedded images read from their bufferViews.
+///
+/// The returned asset and data are `Send` and `Sync`, so they can be shared
+/// between threads for parallel processing.
+pub fn import<P: AsRef<Path>>(path: P) -> Result<(Gltf, Buffers, Images), Error> {
+    import_with(path, &Options::default())
+}
+
+/// Loads a glTF 1.0 asset together with the contents of its buffers and
+/// images, using the given options.
+pub fn import_with<P: AsRef<Path>>(
+    path: P,
+    options: &Options,
+) -> Result<(Gltf, Buffers, Images), Error> {
+    let path = path.as_ref();
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+    import_slice(&read_file(path)?, &Directory(base.to_path_buf()), options)
+}
+
+/// Loads a glTF 1.0 asset together with the contents of its buffers and
+/// images, reading external files through the given cache.
+///
+/// This avoids re-reading files shared by several assets, such as common
+/// textures. Each returned asset still holds its own copy of their data.
+pub fn import_cached<P: AsRef<Path>>(
+    path: P,
+    options: &Options,
+    cache: &FileCache,
+) -> Result<(Gltf, Buffers, Images), Error> {
+    let path = path.as_ref();
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+    import_slice(&read_file(path)?, &cache.directory(base), options)
+}
+
+/// Loads a glTF 1.0 asset held in memory, reading relative URIs from the given
+/// source.
+///
+/// This performs no filesystem access of its own, so it can be used on
+/// targets without a filesystem, such as `wasm32-unknown-unknown`.
+pub fn import_slice<S: Source>(
+    data: &[u8],
+    source: &S,
+    options: &Options,
+) -> Result<(Gltf, Buffers, Images), Error> {
+    let (mut gltf, body) = if binary::is_binary(data) {
+        let glb = binary::Glb::from_slice(data)?;
+        (serde_json::from_slice::<Gltf>(glb.content)?, Some(glb.body))
+    } else {
+        (serde_json::from_slice::<Gltf>(data)?, None)
+    };
+    let version = &gltf.asset.version;
+    if !version.is_empty() && gltf.asset.version().map(|v| v.major) != Some(1) {
+        return Err(Error::UnsupportedVersion(version.clone()));
+    }
+    let errors: Vec<_> = gltf.validate_with(options.validation)
+        .into_iter()
+        .filter(|error| error.severity == Severity::Error)
+        .collect();
+    if !errors.is_empty() {
+        return Err(Error::Validation(errors));
+    }
+
+    let mut buffers = Buffers::new();
+    for (id, buffer) in &gltf.buffers {
+        let data = match body {
+            Some(body) if id == binary::BUFFER_ID => Arc::from(body),
+            _ => {
+                let pointer = Pointer::root().field("buffers").field(id);
+                read_uri(source, &buffer.uri)
+                    .map_err(|err| load_error(pointer, &buffer.uri, err))?
+            },
+        };
+        if data.len() < buffer.byte_length {
+            return Err(Error::BufferLength(id.clone()));
+        }
+        if options.verify_checksums {
+            let pointer = Pointer::root().field("buffers").field(id);
+            verify_checksum(pointer, &buffer.uri, &buffer.extras, &data)?;
+        }
+        buffers.insert(id.clone(), data);
+    }
+    let mut images = Images::new();
+    for (id, image) in &gltf.images {
+        let data = match image.binary() {
+            Some(embedded) => gltf.buffer_views.get(&embedded.buffer_view)
+                .and_then(|view| view.data(&buffers))
+                .ok_or_else(|| Error::BufferView(embedded.buffer_view.clone()))?
+                .into(),
+            None => {
+                let pointer = Pointer::root().field("images").field(id);
+                read_uri(source, &image.uri)
+                    .map_err(|err| load_error(pointer, &image.uri, err))?
+            },
+        };
+        if options.verify_checksums {
+            let pointer = Pointer::root().field("images").field(id);
+            verify_checksum(pointer, &image.uri, &image.extras, &data)?;
+        }
+        images.insert(id.clone(), Data::new(data));
+    }
+    if options.deinterleave {
+        let buffer = builder::unique_buffer_id(&gltf, &buffers, DEINTERLEAVED_BUFFER);
+        optimize::deinterleave(&mut gltf, &mut buffers, &buffer);
+    }
+    if options.repair_bounds {
+        accessor::update_bounds(&mut gltf, &buffers);
+    }
+    Ok((gltf, buffers, images))
+}
+
+pub(crate) fn load_error(pointer: Pointer, uri: &str, cause: Error) -> Error {
+    Error::Load {
+        pointer,
+        uri: uri.to_string(),
+        cause: Box::new(cause),
+    }
+}
+
+/// Returns the SHA-256 digest of `data` as a lowercase hexadecimal string, as
+/// declared in extras for `Options::verify_checksums`.
+pub fn sha256(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn verify_checksum(
+    pointer: Pointer,
+    uri: &str,
+    extras: &Extras,
+    data: &[u8],
+) -> Result<(), Error> {
+    let expected = match extras.value().get("sha256").and_then(|value| value.as_str()) {
+        Some(expected) => expected,
+        None => return Ok(()),
+    };
+    let found = sha256(data);
+    if expected.eq_ignore_ascii_case(&found) {
+        Ok(())
+    } else {
+        Err(Error::Checksum {
+            pointer,
+            uri: uri.to_string(),
+            expected: expected.to_string(),
+            found,
+        })
+    }
+}
+
+/// Reads the contents of a data URI, or of a relative URI from `source`.
+pub(crate) fn read_uri<S: Source>(source: &S, uri: &str) -> Result<Arc<[u8]>, Error> {
+    if let Some(rest) = uri.strip_prefix("data:") {
+        let comma = rest.find(',')
+            .ok_or_else(|| Error::UnsupportedUri(uri.to_string()))?;
+        let (media_type, data) = (&rest[..comma], &rest[comma + 1..]);
+        if media_type.ends_with(";base64") {
+            base64::decode(data).map(Arc::from).map_err(Error::Base64)
+        } else {
+            Ok(Arc::from(data.as_bytes()))
+        }
+    } else if uri.contains("://") {
+        Err(Error::UnsupportedUri(uri.to_string()))
+    } else {
+        source.read_shared(uri)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    /// Creates an empty directory for a test, unique to the test and process.
+    fn temp_dir(test: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("gltf-v1-{}-{}", test, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn imported_assets_are_send_and_sync() {
+        assert_send_sync::<(Gltf, Buffers, Images)>();
+        assert_send_sync::<FileCache>();
+    }
+
+    #[test]
+    fn it_reads_data_uris() {
+        let base = HashMap::new();
+        let data = read_uri(&base, "data:application/octet-stream;base64,AAEC").unwrap();
+        assert_eq!(vec![0, 1, 2], *data);
+        assert_eq!(b"abc", &*read_uri(&base, "data:text/plain,abc").unwrap());
+        match read_uri(&base, "http://example.com/buffer.bin") {
+            Err(error @ Error::UnsupportedUri(_)) => {
+                assert_eq!("unsupported URI: http://example.com/buffer.bin",
+                           error.to_string());
+            },
+            result => panic!("expected an unsupported URI, found {:?}", result),
+        }
+    }
+
+    /// A source relying on the default `read_range`.
+    struct Whole(Vec<u8>);
+
+    impl Source for Whole {
+        fn read(&self, _: &str) -> Result<Vec<u8>, Error> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn it_reads_ranges() {
+        let mut memory = HashMap::new();
+        memory.insert("buffer.bin".to_string(), vec![0, 1, 2, 3]);
+        let whole = Whole(vec![0, 1, 2, 3]);
+        assert_eq!(vec![1, 2], memory.read_range("buffer.bin", 1, 2).unwrap());
+        assert_eq!(vec![1, 2], whole.read_range("buffer.bin", 1, 2).unwrap());
+        for &(offset, len) in &[(3, 2), (1, usize::MAX), (u64::MAX, 1)] {
+            let results = [memory.read_range("buffer.bin", offset, len),
+                           whole.read_range("buffer.bin", offset, len)];
+            for result in &results {
+                match *result {
+                    Err(Error::Io(ref err)) => {
+                        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+                    },
+                    ref result => panic!("expected an I/O error, found {:?}", result),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_resolves_directory_uris() {
+        let directory = Directory(PathBuf::from("assets"));
+        let path = directory.resolve("textures/My%20Texture.png").unwrap();
+        assert_eq!(Path::new("assets/textures/My Texture.png"), path);
+        let path = directory.resolve("../shared/Wood.png").unwrap();
+        assert_eq!(Path::new("assets/../shared/Wood.png"), path);
+        for uri in &["/etc/passwd", "%2Fetc/passwd", "bad%2", "bad%zz.png", "bad%+f"] {
+            match directory.resolve(uri) {
+                Err(Error::UnsupportedUri(ref found)) if found == uri => {},
+                result => panic!("expected {} to be unsupported, found {:?}", uri, result),
+            }
+        }
+    }
+
+    #[test]
+    fn it_imports_buffers_and_images() {
+        let dir = temp_dir("it_imports_buffers_and_images");
+        fs::write(dir.join("image.png"), b"\x89PNG\r\n\x1a\n").unwrap();
+        fs::write(dir.join("asset.gltf"), r#"{
+            "buffers": {
+                "inline": {
+                    "uri": "data:application/octet-stream;base64,AAEC",
+                    "byteLength": 3
+                }
+            },
+            "images": {
+                "image": { "uri": "image.png" }
+            }
+        }"#).unwrap();
+        let (_, buffers, images) = import(dir.join("asset.gltf")).unwrap();
+        assert_eq!(vec![0, 1, 2], *buffers["inline"]);
+        assert_eq!(Some("image/png"), images["image"].mime_type());
+
+        fs::write(dir.join("short.gltf"), r#"{
+            "buffers": {
+                "inline": {
+                    "uri": "data:application/octet-stream;base64,AAEC",
+                    "byteLength": 4
+                }
+            }
+        }"#).unwrap();
+        match import(dir.join("short.gltf")) {
+            Err(Error::BufferLength(ref id)) if id == "inline" => {},
+            result => panic!("expected a buffer length error, found {:?}", result),
+        }
+        let missing = dir.join("missing.gltf");
+        match import(&missing) {
+            Err(Error::Io(ref err)) => {
+                assert_eq!(io::ErrorKind::NotFound, err.kind());
+                assert!(err.to_string().starts_with(&missing.display().to_string()));
+            },
+            result => panic!("expected a missing file, found {:?}", result),
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn it_caches_shared_files() {
+        let dir = temp_dir("it_caches_shared_files");
+        fs::create_dir_all(dir.join("a")).unwrap();
+        fs::write(dir.join("shared.bin"), [1, 2]).unwrap();
+        fs::write(dir.join("first.gltf"), r#"{
+            "buffers": {
+                "shared": { "uri": "shared.bin", "byteLength": 2 }
+            }
+        }"#).unwrap();
+        fs::write(dir.join("a").join("second.gltf"), r#"{
+            "buffers": {
+                "shared": { "uri": "../shared.bin", "byteLength": 2 }
+            }
+        }"#).unwrap();
+
+        let cache = FileCache::new();
+        let options = Options::default();
+        let path = dir.join("first.gltf");
+        let (_, first, _) = import_cached(&path, &options, &cache).unwrap();
+        assert_eq!(1, cache.len());
+        fs::write(dir.join("shared.bin"), [3, 4]).unwrap();
+        let second = dir.join("a").join("second.gltf");
+        let (_, second, _) = import_cached(&second, &options, &cache).unwrap();
+        assert_eq!(1, cache.len());
+        assert!(Arc::ptr_eq(&first["shared"], &second["shared"]));
+        let range = cache.directory(&dir).read_range("shared.bin", 1, 1).unwrap();
+        assert_eq!(vec![2], range);
+
+        cache.clear();
+        let (_, third, _) = import_cached(&path, &options, &cache).unwrap();
+        assert_eq!(vec![3, 4], *third["shared"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn it_imports_from_memory() {
+        let json = br#"{
+            "buffers": {
+                "external": { "uri": "buffer.bin", "byteLength": 2 }
+            }
+        }"#;
+        let mut source = HashMap::new();
+        source.insert("buffer.bin".to_string(), vec![4, 2]);
+        let (_, buffers, _) = import_slice(json, &source, &Options::default()).unwrap();
+        assert_eq!(vec![4, 2], *buffers["external"]);
+        match import_slice(json, &HashMap::new(), &Options::default()) {
+            Err(Error::Load { ref pointer, ref uri, ref cause }) => {
+                assert_eq!("/buffers/external", pointer.as_str());
+                assert_eq!("buffer.bin", uri);
+                assert!(matches!(**cause, Error::Io(ref error)
+                                 if error.kind() == io::ErrorKind::NotFound));
+            },
+            result => panic!("expected a missing file, found {:?}", result),
+        }
+    }
+
+    #[test]
+    fn it_deinterleaves_into_a_new_buffer() {
+        let json = br#"{
+            "accessors": {
+                "values": {
+                    "bufferView": "view", "byteOffset": 0, "byteStride": 8,
+                    "componentType": 5126, "count": 2, "type": "SCALAR"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "deinterleaved", "byteLength": 12 }
+            },
+            "buffers": {
+                "deinterleaved": { "uri": "buffer.bin", "byteLength": 12 }
+            }
+        }"#;
+        let data: Vec<u8> = [1.0f32, 0.0, 2.0].iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        let mut source = HashMap::new();
+        source.insert("buffer.bin".to_string(), data.clone());
+        let options = Options { deinterleave: true, ..Default::default() };
+        let (gltf, buffers, _) = import_slice(json, &source, &options).unwrap();
+        assert_eq!(data, *buffers["deinterleaved"]);
+        assert_eq!(12, gltf.buffers["deinterleaved"].byte_length);
+        let accessor = &gltf.accessors["values"];
+        assert_eq!("deinterleaved_1", gltf.buffer_views[&accessor.buffer_view].buffer);
+        let read: Vec<f32> = accessor.view(&gltf, &buffers).unwrap().iter().collect();
+        assert_eq!(vec![1.0, 2.0], read);
+    }
+
+    #[test]
+    fn it_verifies_checksums() {
+        assert_eq!("039058c6f2c0cb492c533b0a4d14ef77cc0f78abccced5287d84a1a2011cfb81",
+                   sha256(&[1, 2, 3]));
+        let json = format!(r#"{{
+            "buffers": {{
+                "external": {{
+                    "uri": "buffer.bin",
+                    "byteLength": 3,
+                    "extras": {{ "sha256": "{}" }}
+                }}
+            }}
+        }}"#, sha256(&[1, 2, 3]).to_uppercase());
+        let json = json.as_bytes();
+        let options = Options { verify_checksums: true, ..Default::default() };
+        let mut source = HashMap::new();
+        source.insert("buffer.bin".to_string(), vec![1, 2, 3]);
+        assert!(import_slice(json, &source, &options).is_ok());
+
+        source.insert("buffer.bin".to_string(), vec![1, 2, 4]);
+        assert!(import_slice(json, &source, &Options::default()).is_ok());
+        match import_slice(json, &source, &options) {
+            Err(Error::Checksum { ref pointer, ref uri, ref found, .. }) => {
+                assert_eq!("/buffers/external", pointer.as_str());
+                assert_eq!("buffer.bin", uri);
+                assert_eq!(&sha256(&[1, 2, 4]), found);
+            },
+            result => panic!("expected a checksum mismatch, found {:?}", result),
+        }
+    }
+
+    #[test]
+    fn it_rejects_other_versions() {
+        let json = br#"{ "asset": { "version": "2.0", "profile": {} } }"#;
+        match import_slice(json, &HashMap::new(), &Options::default()) {
+            Err(Error::UnsupportedVersion(ref version)) if version == "2.0" => {},
+            result => panic!("expected an unsupported version, found {:?}", result),
+        }
+        let json = br#"{ "asset": { "version": "1.0.1", "profile": {} } }"#;
+        assert!(import_slice(json, &HashMap::new(), &Options::default()).is_ok());
+    }
+
+    #[test]
+    fn it_imports_binary_containers() {
+        let content = br#"{
+            "buffers": {
+                "binary_glTF": { "uri": "data:,", "byteLength": 10 }
+            },
+            "bufferViews": {
+                "view": { "buffer": "binary_glTF", "byteOffset": 2, "byteLength": 8 }
+            },
+            "images": {
+                "image": {
+                    "uri": "data:,",
+                    "extensions": {
+                        "KHR_binary_glTF": {
+                            "bufferView": "view",
+                            "mimeType": "image/png",
+                            "width": 1,
+                            "height": 1
+                        }
+                    }
+                }
+            }
+        }"#;
+        let body = b"\0\0\x89PNG\r\n\x1a\n";
+        let length = binary::HEADER_LENGTH + content.len() + body.len();
+        let mut data = binary::MAGIC.to_vec();
+        for word in &[binary::VERSION, length as u32, content.len() as u32, 0] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        data.extend_from_slice(content);
+        data.extend_from_slice(body);
+
+        let dir = temp_dir("it_imports_binary_containers");
+        fs::write(dir.join("asset.glb"), &data).unwrap();
+        let (gltf, buffers, images) = import(dir.join("asset.glb")).unwrap();
+        assert_eq!(body.to_vec(), *buffers[binary::BUFFER_ID]);
+        assert_eq!(Some("image/png"), gltf.images["image"].mime_type());
+        assert_eq!(Some("image/png"), images["image"].mime_type());
+        assert_eq!(&body[2..], images["image"].encoded());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
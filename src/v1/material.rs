@@ -6,7 +6,13 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use v1::Gltf;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Material {
     /// The ID of the technique.
     ///
@@ -15,10 +21,74 @@ pub struct Material {
     /// default material with 50% gray emissive color
     pub technique: Option<String>,
 
-    // TODO: implement values
+    /// A dictionary object of parameter values.
+    ///
+    /// Parameters with the same name as the technique's parameter override the
+    /// technique's parameter value.
+    #[serde(default)]
+    pub values: HashMap<String, Value>,
+
     /// The user-defined name of this object.
     ///
     /// This is not necessarily unique, e.g., a material and a buffer could have
     /// the same name, or two materials could even have the same name.
     pub name: Option<String>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
 }
+
+impl Material {
+    /// Returns the material that primitives are rendered with when no
+    /// material properties are defined, i.e. 50% gray emissive color.
+    pub fn default_material() -> Material {
+        let mut values = HashMap::new();
+        let emission = vec![0.5, 0.5, 0.5, 1.0];
+        values.insert("emission".to_string(), Value::from(emission));
+        Material {
+            technique: None,
+            values,
+            name: None,
+            extensions: Extensions::default(),
+            extras: Extras::default(),
+        }
+    }
+
+    /// Returns the textures sampled by this material as pairs of parameter
+    /// name and texture ID, sorted by parameter name.
+    ///
+    /// The value of each parameter is taken from the material, falling back to
+    /// the value in the technique. Values that name no texture are ignored.
+    pub fn textures<'a>(&'a self, gltf: &'a Gltf) -> Vec<(&'a str, &'a str)> {
+        let technique = self.technique.as_ref().and_then(|id| gltf.techniques.get(id));
+        let mut names: BTreeSet<&str> = self.values.keys().map(String::as_str).collect();
+        if let Some(technique) = technique {
+            names.extend(technique.parameters.keys().map(String::as_str));
+        }
+        names.into_iter()
+            .filter_map(|name| {
+                let value = self.values.get(name).or_else(|| {
+                    technique?.parameters.get(name)?.value.as_ref()
+                })?;
+                let id = value.as_str()?;
+                if gltf.textures.contains_key(id) {
+                    Some((name, id))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl_references!(Material {
+    ids { technique: "technique" => techniques, }
+    nested {}
+});
@@ -0,0 +1,132 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::HashMap;
+use v1::Gltf;
+use v1::buffer::{Buffers, Target};
+use v1::builder::Builder;
+use v1::mesh::{Mode, Primitive, Semantic};
+use v1::scene::Flattened;
+
+/// Triangle geometry in the plain layout used by most geometry libraries.
+///
+/// Every attribute is either empty or has one element per vertex.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IndexedMesh {
+    /// Vertex positions.
+    pub positions: Vec<[f32; 3]>,
+
+    /// Vertex normals.
+    pub normals: Vec<[f32; 3]>,
+
+    /// Texture co-ordinates of set 0.
+    pub tex_coords: Vec<[f32; 2]>,
+
+    /// Three vertex indices per counter-clockwise triangle.
+    pub indices: Vec<u32>,
+}
+
+impl IndexedMesh {
+    /// Reads the triangles of a primitive, converting strips and fans to a
+    /// triangle list.
+    ///
+    /// Returns `None` if the primitive has no `POSITION` attribute, or if the
+    /// data of an attribute or the indices cannot be read from `buffers`.
+    pub fn read(
+        gltf: &Gltf,
+        buffers: &Buffers,
+        primitive: &Primitive,
+    ) -> Option<IndexedMesh> {
+        let read = |semantic: Semantic| -> Option<Option<Vec<_>>> {
+            match primitive.attribute(&semantic) {
+                Some(id) => {
+                    let view = gltf.accessors.get(id)?.view(gltf, buffers)?;
+                    Some(Some(view.iter().collect()))
+                },
+                None => Some(None),
+            }
+        };
+        let positions = read(Semantic::Position)??;
+        let normals = read(Semantic::Normal)?.unwrap_or_default();
+        let tex_coords = match primitive.attribute(&Semantic::TexCoord(0)) {
+            Some(id) => gltf.accessors.get(id)?.view(gltf, buffers)?.iter().collect(),
+            None => Vec::new(),
+        };
+        let indices = primitive.read_indices(gltf, buffers)?;
+        Some(IndexedMesh {
+            positions,
+            normals,
+            tex_coords,
+            indices: primitive.triangles(indices).flatten().collect(),
+        })
+    }
+
+    /// Writes the mesh as a new indexed triangle primitive.
+    ///
+    /// Accessors named after `id` are appended to the builder's buffer.
+    /// Indices are written as `UNSIGNED_SHORT` when every index fits, and as
+    /// `UNSIGNED_INT` otherwise.
+    pub fn write(&self, builder: &mut Builder, id: &str, material: &str) -> Primitive {
+        let mut attributes = HashMap::new();
+        let position = builder.accessor(&format!("{}_positions", id), &self.positions,
+                                        Some(Target::ArrayBuffer));
+        attributes.insert("POSITION".to_string(), position);
+        if !self.normals.is_empty() {
+            let normal = builder.accessor(&format!("{}_normals", id), &self.normals,
+                                          Some(Target::ArrayBuffer));
+            attributes.insert("NORMAL".to_string(), normal);
+        }
+        if !self.tex_coords.is_empty() {
+            let tex_coord = builder.accessor(&format!("{}_tex_coords", id),
+                                             &self.tex_coords, Some(Target::ArrayBuffer));
+            attributes.insert("TEXCOORD_0".to_string(), tex_coord);
+        }
+        let indices = builder.indices(&format!("{}_indices", id), &self.indices);
+        Primitive {
+            attributes,
+            indices: Some(indices),
+            material: material.to_string(),
+            mode: Mode::Triangles,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<Flattened> for IndexedMesh {
+    fn from(flattened: Flattened) -> IndexedMesh {
+        IndexedMesh {
+            positions: flattened.positions,
+            normals: flattened.normals,
+            tex_coords: Vec::new(),
+            indices: flattened.indices,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_through_primitives() {
+        let mesh = IndexedMesh {
+            positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+            normals: vec![[0.0, 0.0, 1.0]; 3],
+            tex_coords: Vec::new(),
+            indices: vec![0, 1, 2],
+        };
+        let mut gltf = Gltf::default();
+        let mut buffers = Buffers::new();
+        let primitive = {
+            let mut builder = Builder::new(&mut gltf, &mut buffers, "buffer");
+            mesh.write(&mut builder, "triangle", "material")
+        };
+        assert_eq!(Some("triangle_indices"), primitive.indices.as_deref());
+        assert_eq!(Some(mesh), IndexedMesh::read(&gltf, &buffers, &primitive));
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Vertex buffer layouts of primitives, in the shape expected by wgpu-style
+//! APIs.
+//!
+//! Attributes sharing a bufferView and stride are grouped into one interleaved
+//! buffer; every other attribute gets a buffer of its own.
+
+use v1::Gltf;
+use v1::accessor::{ComponentType, Kind};
+use v1::mesh::{Primitive, Semantic};
+
+/// The format of a vertex attribute, named after wgpu's `VertexFormat`.
+///
+/// Integer components are read as integers by the shader.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum VertexFormat {
+    /// Two unsigned 8-bit integers.
+    Uint8x2,
+
+    /// Four unsigned 8-bit integers.
+    Uint8x4,
+
+    /// Two signed 8-bit integers.
+    Sint8x2,
+
+    /// Four signed 8-bit integers.
+    Sint8x4,
+
+    /// Two unsigned 16-bit integers.
+    Uint16x2,
+
+    /// Four unsigned 16-bit integers.
+    Uint16x4,
+
+    /// Two signed 16-bit integers.
+    Sint16x2,
+
+    /// Four signed 16-bit integers.
+    Sint16x4,
+
+    /// One 32-bit float.
+    Float32,
+
+    /// Two 32-bit floats.
+    Float32x2,
+
+    /// Three 32-bit floats.
+    Float32x3,
+
+    /// Four 32-bit floats.
+    Float32x4,
+
+    /// One unsigned 32-bit integer.
+    Uint32,
+
+    /// Two unsigned 32-bit integers.
+    Uint32x2,
+
+    /// Three unsigned 32-bit integers.
+    Uint32x3,
+
+    /// Four unsigned 32-bit integers.
+    Uint32x4,
+}
+
+impl VertexFormat {
+    /// Returns the format of elements with the given layout.
+    ///
+    /// `None` is returned for matrices, 64-bit floats, signed 32-bit integers,
+    /// and layouts without a vertex format such as three 8-bit integers.
+    pub fn new(component_type: ComponentType, kind: Kind) -> Option<VertexFormat> {
+        use self::VertexFormat::*;
+        let format = match (component_type, kind) {
+            (ComponentType::U8, Kind::Vec2) => Uint8x2,
+            (ComponentType::U8, Kind::Vec4) => Uint8x4,
+            (ComponentType::I8, Kind::Vec2) => Sint8x2,
+            (ComponentType::I8, Kind::Vec4) => Sint8x4,
+            (ComponentType::U16, Kind::Vec2) => Uint16x2,
+            (ComponentType::U16, Kind::Vec4) => Uint16x4,
+            (ComponentType::I16, Kind::Vec2) => Sint16x2,
+            (ComponentType::I16, Kind::Vec4) => Sint16x4,
+            (ComponentType::F32, Kind::Scalar) => Float32,
+            (ComponentType::F32, Kind::Vec2) => Float32x2,
+            (ComponentType::F32, Kind::Vec3) => Float32x3,
+            (ComponentType::F32, Kind::Vec4) => Float32x4,
+            (ComponentType::U32, Kind::Scalar) => Uint32,
+            (ComponentType::U32, Kind::Vec2) => Uint32x2,
+            (ComponentType::U32, Kind::Vec3) => Uint32x3,
+            (ComponentType::U32, Kind::Vec4) => Uint32x4,
+            _ => return None,
+        };
+        Some(format)
+    }
+
+    /// Returns the size of an element in bytes.
+    pub fn size(self) -> u64 {
+        use self::VertexFormat::*;
+        match self {
+            Uint8x2 | Sint8x2 => 2,
+            Uint8x4 | Sint8x4 | Uint16x2 | Sint16x2 | Float32 | Uint32 => 4,
+            Uint16x4 | Sint16x4 | Float32x2 | Uint32x2 => 8,
+            Float32x3 | Uint32x3 => 12,
+            Float32x4 | Uint32x4 => 16,
+        }
+    }
+}
+
+/// A vertex attribute within a vertex buffer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexAttribute {
+    /// The attribute semantic.
+    pub semantic: Semantic,
+
+    /// The format of each element.
+    pub format: VertexFormat,
+
+    /// The offset of the attribute within each vertex in bytes.
+    pub offset: u64,
+
+    /// The shader input location of the attribute.
+    pub shader_location: u32,
+}
+
+/// The layout of a vertex buffer bound for a primitive.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexBufferLayout {
+    /// The ID of the bufferView holding the vertex data.
+    pub buffer_view: String,
+
+    /// The offset of the first vertex within the bufferView in bytes.
+    pub offset: u64,
+
+    /// The distance between consecutive vertices in bytes.
+    pub array_stride: u64,
+
+    /// The attributes read from each vertex.
+    pub attributes: Vec<VertexAttribute>,
+}
+
+/// Returns the vertex buffer layouts needed to draw a primitive.
+///
+/// Shader locations are assigned in the alphabetical order of the attribute
+/// names, starting from 0, and buffers are ordered by their lowest location.
+/// `None` is returned if an attribute accessor is missing or has no
+/// `VertexFormat`.
+pub fn vertex_buffers(
+    gltf: &Gltf,
+    primitive: &Primitive,
+) -> Option<Vec<VertexBufferLayout>> {
+    let mut names: Vec<_> = primitive.attributes.iter().collect();
+    names.sort();
+    let mut attributes = Vec::with_capacity(names.len());
+    for (location, (name, id)) in names.into_iter().enumerate() {
+        let accessor = gltf.accessors.get(id)?;
+        let format = VertexFormat::new(accessor.component_type, accessor.kind)?;
+        let attribute = VertexAttribute {
+            semantic: Semantic::from_attribute(name),
+            format,
+            offset: accessor.byte_offset as u64,
+            shader_location: location as u32,
+        };
+        attributes.push((&accessor.buffer_view, accessor.stride() as u64, attribute));
+    }
+    attributes.sort_by_key(|&(view, stride, ref attribute)| {
+        (view, stride, attribute.offset)
+    });
+
+    let mut layouts: Vec<VertexBufferLayout> = Vec::new();
+    for (view, stride, mut attribute) in attributes {
+        let interleaved = layouts.last().is_some_and(|layout| {
+            layout.buffer_view == *view
+                && layout.array_stride == stride
+                && attribute.offset + attribute.format.size() <= layout.offset + stride
+        });
+        if !interleaved {
+            layouts.push(VertexBufferLayout {
+                buffer_view: view.clone(),
+                offset: attribute.offset,
+                array_stride: stride,
+                attributes: Vec::new(),
+            });
+        }
+        let layout = layouts.last_mut().unwrap();
+        attribute.offset -= layout.offset;
+        layout.attributes.push(attribute);
+    }
+    layouts.sort_by_key(|layout| {
+        layout.attributes.iter().map(|attribute| attribute.shader_location).min()
+    });
+    Some(layouts)
+}
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_groups_interleaved_attributes() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "position": {
+                    "bufferView": "interleaved", "byteOffset": 0, "byteStride": 24,
+                    "componentType": 5126, "count": 3, "type": "VEC3"
+                },
+                "normal": {
+                    "bufferView": "interleaved", "byteOffset": 12, "byteStride": 24,
+                    "componentType": 5126, "count": 3, "type": "VEC3"
+                },
+                "tex_coord": {
+                    "bufferView": "interleaved", "byteOffset": 72,
+                    "componentType": 5126, "count": 3, "type": "VEC2"
+                }
+            }
+        }"#).unwrap();
+        let primitive: Primitive = serde_json::from_str(r#"{
+            "attributes": {
+                "POSITION": "position",
+                "NORMAL": "normal",
+                "TEXCOORD_0": "tex_coord"
+            },
+            "material": "material"
+        }"#).unwrap();
+        let layouts = vertex_buffers(&gltf, &primitive).unwrap();
+        assert_eq!(2, layouts.len());
+        assert_eq!((0, 24), (layouts[0].offset, layouts[0].array_stride));
+        let attributes: Vec<_> = layouts[0].attributes.iter()
+            .map(|a| (a.semantic.clone(), a.offset, a.shader_location))
+            .collect();
+        assert_eq!(vec![(Semantic::Position, 0, 1), (Semantic::Normal, 12, 0)], attributes);
+        assert_eq!((72, 8), (layouts[1].offset, layouts[1].array_stride));
+        assert_eq!(VertexFormat::Float32x2, layouts[1].attributes[0].format);
+        assert_eq!(0, layouts[1].attributes[0].offset);
+    }
+}
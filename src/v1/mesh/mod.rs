@@ -0,0 +1,1423 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::collections::hash_map;
+use std::error::Error as StdError;
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+use std::str::FromStr;
+use v1::Gltf;
+use v1::accessor::{self, Accessor, ComponentType, Kind};
+use v1::buffer::{Buffers, Target};
+use v1::builder::Builder;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+use v1::material::Material;
+use v1::math;
+use v1::validation::{report, report_value, Code, Error, Pointer, Severity, Validate};
+
+mod indexed;
+#[cfg(feature = "layout")]
+pub mod layout;
+pub mod simplify;
+pub mod tangents;
+mod weld;
+
+/// The name of the extension allowing `indices` with 32-bit components.
+pub const ELEMENT_INDEX_UINT: &str = "OES_element_index_uint";
+
+pub use self::indexed::IndexedMesh;
+pub use self::weld::{weld, weld_primitive};
+
+enum_number! {
+    Mode {
+        Points = 0,
+        Lines = 1,
+        LineLoop = 2,
+        LineStrip = 3,
+        Triangles = 4,
+        TriangleStrip = 5,
+        TriangleFan = 6,
+    }
+}
+
+impl Default for Mode {
+    fn default() -> Mode {
+        Mode::Triangles
+    }
+}
+
+impl Mode {
+    /// Returns an iterator over the triangles formed by `indices` when rendered
+    /// with this mode.
+    ///
+    /// Strips and fans are converted to triangle lists, preserving winding
+    /// order. Points and lines yield no triangles.
+    pub fn triangles<I>(self, indices: I) -> Triangles<I::IntoIter>
+        where I: IntoIterator<Item = u32>
+    {
+        Triangles {
+            mode: self,
+            indices: indices.into_iter(),
+            first: None,
+            previous: None,
+            parity: false,
+        }
+    }
+
+    /// Returns the number of triangles formed by `count` indices when
+    /// rendered with this mode, without reading them.
+    pub fn triangle_count(self, count: usize) -> usize {
+        match self {
+            Mode::Triangles => count / 3,
+            Mode::TriangleStrip | Mode::TriangleFan => count.saturating_sub(2),
+            Mode::Points | Mode::Lines | Mode::LineLoop | Mode::LineStrip => 0,
+        }
+    }
+}
+
+/// An `Iterator` over the triangles of a primitive.
+///
+/// Each triangle is given as the three vertex indices in counter-clockwise
+/// order.
+#[derive(Clone, Debug)]
+pub struct Triangles<I> {
+    /// The primitive topology.
+    mode: Mode,
+
+    /// The remaining vertex indices.
+    indices: I,
+
+    /// The first vertex of a fan, or the second last vertex of a strip.
+    first: Option<u32>,
+
+    /// The last vertex seen by a fan or strip.
+    previous: Option<u32>,
+
+    /// Whether the next strip triangle has reversed winding.
+    parity: bool,
+}
+
+impl<I: Iterator<Item = u32>> Iterator for Triangles<I> {
+    type Item = [u32; 3];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.mode {
+            Mode::Triangles => {
+                let a = self.indices.next()?;
+                let b = self.indices.next()?;
+                let c = self.indices.next()?;
+                Some([a, b, c])
+            },
+            Mode::TriangleStrip => {
+                if self.first.is_none() {
+                    self.first = self.indices.next();
+                    self.previous = self.indices.next();
+                }
+                let a = self.first?;
+                let b = self.previous?;
+                let c = self.indices.next()?;
+                self.first = Some(b);
+                self.previous = Some(c);
+                self.parity = !self.parity;
+                if self.parity {
+                    Some([a, b, c])
+                } else {
+                    Some([b, a, c])
+                }
+            },
+            Mode::TriangleFan => {
+                if self.first.is_none() {
+                    self.first = self.indices.next();
+                    self.previous = self.indices.next();
+                }
+                let a = self.first?;
+                let b = self.previous?;
+                let c = self.indices.next()?;
+                self.previous = Some(c);
+                Some([a, b, c])
+            },
+            Mode::Points | Mode::Lines | Mode::LineLoop | Mode::LineStrip => None,
+        }
+    }
+}
+
+/// Vertex attribute semantic names.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Semantic {
+    /// XYZ vertex positions.
+    Position,
+
+    /// XYZ vertex normals.
+    Normal,
+
+    /// UV texture co-ordinates of the given set.
+    TexCoord(u32),
+
+    /// RGB or RGBA vertex color of the given set.
+    Color(u32),
+
+    /// Joint indices of the given set.
+    Joint(u32),
+
+    /// Joint weights of the given set.
+    Weight(u32),
+
+    /// Any other semantic.
+    ///
+    /// Application-specific semantics, such as `_TEMPERATURE` or `_BATCHID`,
+    /// must start with an underscore. Other names are kept as read, e.g. the
+    /// common but non-standard `TANGENT`, and are reported by validation.
+    Extra(String),
+}
+
+impl Semantic {
+    /// Returns `true` for application-specific semantics, i.e. `Extra`
+    /// semantics starting with an underscore.
+    pub fn is_application_specific(&self) -> bool {
+        match *self {
+            Semantic::Extra(ref name) => name.starts_with('_'),
+            _ => false,
+        }
+    }
+
+    /// Interprets an attribute name of the form `[semantic]` or
+    /// `[semantic]_[set_index]`.
+    ///
+    /// A missing set index means set 0. The glTF 2.0 spellings `JOINTS_n` and
+    /// `WEIGHTS_n` are accepted as aliases of `JOINT_n` and `WEIGHT_n`.
+    pub(crate) fn from_attribute(name: &str) -> Semantic {
+        let (prefix, set) = match name.rfind('_') {
+            Some(i) if i > 0 => match name[i + 1..].parse::<u32>() {
+                Ok(set) => (&name[..i], Some(set)),
+                Err(_) => (name, None),
+            },
+            _ => (name, None),
+        };
+        match (prefix, set) {
+            ("POSITION", None) => Semantic::Position,
+            ("NORMAL", None) => Semantic::Normal,
+            ("TEXCOORD", set) => Semantic::TexCoord(set.unwrap_or(0)),
+            ("COLOR", set) => Semantic::Color(set.unwrap_or(0)),
+            ("JOINT", set) | ("JOINTS", set) => Semantic::Joint(set.unwrap_or(0)),
+            ("WEIGHT", set) | ("WEIGHTS", set) => Semantic::Weight(set.unwrap_or(0)),
+            _ => Semantic::Extra(name.to_string()),
+        }
+    }
+}
+
+/// The names of the standard semantics, without set indices.
+const STANDARD_SEMANTICS: [&str; 8] = [
+    "POSITION", "NORMAL", "TEXCOORD", "COLOR", "JOINT", "JOINTS", "WEIGHT", "WEIGHTS",
+];
+
+impl FromStr for Semantic {
+    type Err = ParseSemanticError;
+
+    /// Parses an attribute name such as `TEXCOORD_3`, `JOINT`, or `_BATCHID`.
+    ///
+    /// Unlike the lenient interpretation used when reading assets, a standard
+    /// semantic with a malformed set index, such as `TEXCOORD_x` or
+    /// `TEXCOORD_01`, or with a set index it does not allow, such as
+    /// `POSITION_1`, is rejected instead of being taken as an `Extra`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        if name.is_empty() {
+            return Err(ParseSemanticError::Empty);
+        }
+        let semantic = Semantic::from_attribute(name);
+        if let Some(i) = name.rfind('_') {
+            let (prefix, set) = (&name[..i], &name[i + 1..]);
+            let canonical = set.parse::<u32>().is_ok_and(|n| n.to_string() == set);
+            let extra = matches!(semantic, Semantic::Extra(_));
+            if STANDARD_SEMANTICS.contains(&prefix) && (extra || !canonical) {
+                return Err(ParseSemanticError::SetIndex(name.to_string()));
+            }
+        }
+        Ok(semantic)
+    }
+}
+
+impl fmt::Display for Semantic {
+    /// Writes the canonical attribute name, e.g. `TEXCOORD_3` or `JOINT_0`,
+    /// which parses back to the same semantic.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Semantic::Position => f.write_str("POSITION"),
+            Semantic::Normal => f.write_str("NORMAL"),
+            Semantic::TexCoord(set) => write!(f, "TEXCOORD_{}", set),
+            Semantic::Color(set) => write!(f, "COLOR_{}", set),
+            Semantic::Joint(set) => write!(f, "JOINT_{}", set),
+            Semantic::Weight(set) => write!(f, "WEIGHT_{}", set),
+            Semantic::Extra(ref name) => f.write_str(name),
+        }
+    }
+}
+
+/// Problems with an attribute name, returned by `Semantic::from_str`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseSemanticError {
+    /// The name is empty.
+    Empty,
+    /// The name of a standard semantic has a malformed or disallowed set
+    /// index, e.g. `TEXCOORD_x` or `POSITION_1`.
+    SetIndex(String),
+}
+
+impl fmt::Display for ParseSemanticError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseSemanticError::Empty => f.write_str("empty attribute semantic"),
+            ParseSemanticError::SetIndex(ref name) => {
+                write!(f, "invalid set index in attribute semantic {}", name)
+            },
+        }
+    }
+}
+
+impl StdError for ParseSemanticError {}
+
+/// An `Iterator` over the attributes of a primitive.
+#[derive(Clone, Debug)]
+pub struct IterAttributes<'a> {
+    /// The internal attribute map iterator.
+    iter: hash_map::Iter<'a, String, String>,
+}
+
+impl<'a> Iterator for IterAttributes<'a> {
+    /// The attribute semantic and the ID of the accessor containing its data.
+    type Item = (Semantic, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(name, id)| (Semantic::from_attribute(name), id.as_str()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for IterAttributes<'a> {}
+
+/// Selects the attributes read by `Primitive::read_vertices`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VertexOptions {
+    /// Whether to read `NORMAL`.
+    pub normal: bool,
+
+    /// The `TEXCOORD` set to read, if any.
+    pub tex_coord: Option<u32>,
+
+    /// The `COLOR` set to read, if any.
+    pub color: Option<u32>,
+
+    /// The `JOINT` and `WEIGHT` set to read, if any.
+    pub skin: Option<u32>,
+}
+
+impl Default for VertexOptions {
+    /// Reads every attribute, using set 0 where applicable.
+    fn default() -> VertexOptions {
+        VertexOptions {
+            normal: true,
+            tex_coord: Some(0),
+            color: Some(0),
+            skin: Some(0),
+        }
+    }
+}
+
+/// A vertex with its commonly used attributes.
+///
+/// Attributes that were not selected, or that the primitive does not have, are
+/// `None`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vertex {
+    /// XYZ position.
+    pub position: [f32; 3],
+
+    /// XYZ normal.
+    pub normal: Option<[f32; 3]>,
+
+    /// UV texture co-ordinates.
+    pub tex_coord: Option<[f32; 2]>,
+
+    /// RGBA color in the range 0.0 to 1.0. RGB colors are given an alpha of
+    /// 1.0, and unsigned byte and short colors are normalized.
+    pub color: Option<[f32; 4]>,
+
+    /// Joint indices.
+    pub joints: Option<[u32; 4]>,
+
+    /// Joint weights.
+    pub weights: Option<[f32; 4]>,
+}
+
+/// An `Iterator` over the vertices of a primitive.
+#[derive(Clone, Debug)]
+pub struct Vertices<'a> {
+    positions: accessor::Iter<'a, [f32; 3]>,
+    normals: Option<accessor::Iter<'a, [f32; 3]>>,
+    tex_coords: Option<accessor::Iter<'a, [f32; 2]>>,
+    colors: Option<accessor::Iter<'a, [f32; 4]>>,
+    /// The value of a color component at full intensity.
+    color_max: f32,
+    opaque: bool,
+    joints: Option<accessor::Iter<'a, [u32; 4]>>,
+    weights: Option<accessor::Iter<'a, [f32; 4]>>,
+}
+
+impl<'a> Iterator for Vertices<'a> {
+    type Item = Vertex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let position = self.positions.next()?;
+        let mut color = self.colors.as_mut().and_then(Iterator::next);
+        if let Some(ref mut color) = color {
+            for component in color.iter_mut() {
+                *component /= self.color_max;
+            }
+            if self.opaque {
+                color[3] = 1.0;
+            }
+        }
+        Some(Vertex {
+            position,
+            normal: self.normals.as_mut().and_then(Iterator::next),
+            tex_coord: self.tex_coords.as_mut().and_then(Iterator::next),
+            color,
+            joints: self.joints.as_mut().and_then(Iterator::next),
+            weights: self.weights.as_mut().and_then(Iterator::next),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.positions.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for Vertices<'a> {}
+
+/// An `Iterator` over the vertex indices of a primitive, widened to `u32`
+/// whatever the component type of the `indices` accessor.
+#[derive(Clone, Debug)]
+pub enum Indices<'a> {
+    /// The values of the `indices` accessor.
+    Accessor(accessor::Iter<'a, u32>),
+
+    /// The range `0..count` of a primitive rendered without indices.
+    Range(Range<u32>),
+}
+
+impl<'a> Iterator for Indices<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match *self {
+            Indices::Accessor(ref mut iter) => iter.next(),
+            Indices::Range(ref mut range) => range.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match *self {
+            Indices::Accessor(ref iter) => iter.size_hint(),
+            Indices::Range(ref range) => range.size_hint(),
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for Indices<'a> {}
+
+/// The tightly packed elements of a primitive attribute, for writing a
+/// selection of them to a new accessor.
+#[derive(Clone, Debug)]
+pub(crate) struct PackedAttribute {
+    /// The attribute name, e.g. `TEXCOORD_0`.
+    pub name: String,
+    pub component_type: ComponentType,
+    pub kind: Kind,
+    data: Vec<u8>,
+}
+
+impl PackedAttribute {
+    /// Reads every attribute of a primitive, sorted by name, returning `None`
+    /// if any has fewer than `count` elements or cannot be read.
+    pub fn read_all(
+        gltf: &Gltf,
+        buffers: &Buffers,
+        primitive: &Primitive,
+        count: usize,
+    ) -> Option<Vec<PackedAttribute>> {
+        let mut attributes = Vec::new();
+        for (name, id) in &primitive.attributes {
+            let accessor = gltf.accessors.get(id)?;
+            let view = accessor.view(gltf, buffers)?;
+            if view.len() < count {
+                return None;
+            }
+            attributes.push(PackedAttribute {
+                name: name.clone(),
+                component_type: accessor.component_type,
+                kind: accessor.kind,
+                data: view.packed(),
+            });
+        }
+        attributes.sort_by(|a, b| a.name.cmp(&b.name));
+        Some(attributes)
+    }
+
+    /// Returns the bytes of the element at `index`.
+    pub fn element(&self, index: usize) -> &[u8] {
+        let size = self.kind.components() * self.component_type.size();
+        &self.data[index * size..(index + 1) * size]
+    }
+
+    /// Appends the elements at the given indices as a new vertex attribute
+    /// accessor named after `id`, and returns its ID.
+    pub fn write<I>(&self, builder: &mut Builder, id: &str, indices: I) -> String
+        where I: IntoIterator<Item = usize>
+    {
+        let bytes: Vec<u8> = indices.into_iter()
+            .flat_map(|index| self.element(index))
+            .cloned()
+            .collect();
+        builder.accessor_bytes(&format!("{}_{}", id, self.name), self.component_type,
+                               self.kind, &bytes, Some(Target::ArrayBuffer))
+    }
+}
+
+/// Narrows vertex indices to `u16`, returning `None` if any index does not
+/// fit.
+///
+/// Writers use this to store indices in the smallest type WebGL 1.0 supports
+/// without extensions.
+pub fn narrow_indices(indices: &[u32]) -> Option<Vec<u16>> {
+    indices.iter()
+        .map(|&i| if i <= u16::MAX as u32 { Some(i as u16) } else { None })
+        .collect()
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Primitive {
+    /// A dictionary object of strings, where each string is the ID of the
+    /// accessor containing an attribute.
+    #[serde(default)]
+    pub attributes: HashMap<String, String>,
+
+    /// The ID of the accessor that contains the indices.
+    ///
+    /// When this is not defined, the primitives should be rendered without
+    /// indices using drawArrays().
+    ///
+    /// When defined, the accessor must contain indices: the bufferView
+    /// referenced by the accessor must have a target equal to 34963
+    /// (ELEMENT_ARRAY_BUFFER); a byteStride that is tightly packed, i.e., 0 or
+    /// the byte size of componentType in bytes; componentType must be 5121
+    /// (UNSIGNED_BYTE) or 5123 (UNSIGNED_SHORT), or 5125 (UNSIGNED_INT) when
+    /// the `OES_element_index_uint` extension is used; and type must be
+    /// "SCALAR".
+    pub indices: Option<String>,
+
+    /// The ID of the material to apply to this primitive when rendering.
+    pub material: String,
+
+    /// The type of primitives to render.
+    ///
+    /// Allowed values are 0 (POINTS), 1 (LINES), 2 (LINE_LOOP), 3
+    /// (LINE_STRIP), 4 (TRIANGLES), 5 (TRIANGLE_STRIP), and 6 (TRIANGLE_FAN).
+    #[serde(default)]
+    pub mode: Mode,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+impl Primitive {
+    /// Returns an iterator over the vertex attributes of this primitive.
+    pub fn iter_attributes(&self) -> IterAttributes<'_> {
+        IterAttributes { iter: self.attributes.iter() }
+    }
+
+    /// Returns the ID of the accessor containing the given attribute.
+    ///
+    /// Arbitrary sets are supported, for example `Semantic::Joint(1)` and
+    /// `Semantic::Weight(1)` for the second set of skinning influences.
+    pub fn attribute(&self, semantic: &Semantic) -> Option<&str> {
+        self.iter_attributes()
+            .find(|(s, _)| s == semantic)
+            .map(|(_, id)| id)
+    }
+
+    /// Sets the accessor containing the given attribute, replacing the
+    /// attribute of the same semantic if there is one.
+    ///
+    /// New attributes are named after the canonical name of the semantic, e.g.
+    /// `TEXCOORD_0` or `_BATCHID`.
+    pub fn set_attribute(&mut self, semantic: &Semantic, accessor: &str) {
+        let name = self.attributes.keys()
+            .find(|name| Semantic::from_attribute(name) == *semantic)
+            .cloned()
+            .unwrap_or_else(|| semantic.to_string());
+        self.attributes.insert(name, accessor.to_string());
+    }
+
+    /// Reads the components of the given attribute in their stored datatype,
+    /// for attributes such as application-specific ones whose datatype is
+    /// only known at runtime.
+    ///
+    /// Returns `None` if the primitive has no such attribute or its data
+    /// cannot be read from `buffers`.
+    pub fn read_attribute(
+        &self,
+        gltf: &Gltf,
+        buffers: &Buffers,
+        semantic: &Semantic,
+    ) -> Option<accessor::Values> {
+        let accessor = gltf.accessors.get(self.attribute(semantic)?)?;
+        Some(accessor.view(gltf, buffers)?.values())
+    }
+
+    /// Returns the material to render this primitive with.
+    ///
+    /// Falls back to `Material::default_material()` when the referenced
+    /// material does not exist, or when it supplies neither a technique nor an
+    /// extension that could define its properties.
+    pub fn material<'a>(&self, gltf: &'a Gltf) -> Cow<'a, Material> {
+        match gltf.materials.get(&self.material) {
+            Some(material)
+                if material.technique.is_some() || !material.extensions.is_empty() =>
+            {
+                Cow::Borrowed(material)
+            },
+            _ => Cow::Owned(Material::default_material()),
+        }
+    }
+
+    /// Returns an iterator over the vertices of this primitive.
+    ///
+    /// Returns `None` if the primitive has no `POSITION` attribute, or if the
+    /// data of any selected attribute cannot be read from `buffers`. Colors
+    /// are read only when their components are floats, unsigned bytes, or
+    /// unsigned shorts; the latter two are normalized to 0.0 to 1.0.
+    pub fn read_vertices<'a>(
+        &self,
+        gltf: &Gltf,
+        buffers: &'a Buffers,
+        options: &VertexOptions,
+    ) -> Option<Vertices<'a>> {
+        let view = |semantic: Option<Semantic>| -> Option<Option<accessor::View<'a>>> {
+            let id = match semantic.and_then(|s| self.attribute(&s)) {
+                Some(id) => id,
+                None => return Some(None),
+            };
+            let accessor = gltf.accessors.get(id)?;
+            accessor.view(gltf, buffers).map(Some)
+        };
+        let positions = view(Some(Semantic::Position))??;
+        let normals = view(if options.normal { Some(Semantic::Normal) } else { None })?;
+        let tex_coords = view(options.tex_coord.map(Semantic::TexCoord))?;
+        let colors = view(options.color.map(Semantic::Color))?;
+        let color_max = match colors.map(|view| view.component_type()) {
+            Some(ComponentType::U8) => 255.0,
+            Some(ComponentType::U16) => 65535.0,
+            _ => 1.0,
+        };
+        let colors = colors.filter(|view| {
+            matches!(view.component_type(),
+                     ComponentType::F32 | ComponentType::U8 | ComponentType::U16)
+        });
+        let joints = view(options.skin.map(Semantic::Joint))?;
+        let weights = view(options.skin.map(Semantic::Weight))?;
+        Some(Vertices {
+            positions: positions.iter(),
+            normals: normals.map(|view| view.iter()),
+            tex_coords: tex_coords.map(|view| view.iter()),
+            opaque: colors.is_some_and(|view| view.components() < 4),
+            colors: colors.map(|view| view.iter()),
+            color_max,
+            joints: joints.map(|view| view.iter()),
+            weights: weights.map(|view| view.iter()),
+        })
+    }
+
+    /// Returns the vertex indices of this primitive.
+    ///
+    /// These are the values of the `indices` accessor or, for primitives
+    /// rendered without indices, `0..count` where `count` is the number of
+    /// vertices. Returns `None` if the indices cannot be read from `buffers`.
+    pub fn read_indices(&self, gltf: &Gltf, buffers: &Buffers) -> Option<Vec<u32>> {
+        Some(self.iter_indices(gltf, buffers)?.collect())
+    }
+
+    /// Returns an iterator over the vertex indices of this primitive, as
+    /// `read_indices` does but without collecting them.
+    pub fn iter_indices<'a>(
+        &self,
+        gltf: &Gltf,
+        buffers: &'a Buffers,
+    ) -> Option<Indices<'a>> {
+        match self.indices {
+            Some(ref id) => {
+                let view = gltf.accessors.get(id)?.view(gltf, buffers)?;
+                Some(Indices::Accessor(view.iter()))
+            },
+            None => {
+                let positions = gltf.accessors.get(self.attribute(&Semantic::Position)?)?;
+                Some(Indices::Range(0..positions.count))
+            },
+        }
+    }
+
+    /// Returns the number of vertices, i.e. the `count` of the `POSITION`
+    /// accessor, without reading any data.
+    ///
+    /// Returns `None` if the primitive has no `POSITION` accessor.
+    pub fn vertex_count(&self, gltf: &Gltf) -> Option<usize> {
+        let accessor = gltf.accessors.get(self.attribute(&Semantic::Position)?)?;
+        Some(accessor.count as usize)
+    }
+
+    /// Returns the number of vertex indices rendered, i.e. the `count` of the
+    /// `indices` accessor or, for primitives rendered without indices, the
+    /// number of vertices.
+    ///
+    /// Returns `None` if the accessor is missing.
+    pub fn index_count(&self, gltf: &Gltf) -> Option<usize> {
+        match self.indices {
+            Some(ref id) => gltf.accessors.get(id).map(|accessor| accessor.count as usize),
+            None => self.vertex_count(gltf),
+        }
+    }
+
+    /// Returns the number of triangles rendered, without reading any data.
+    ///
+    /// Points and lines render no triangles. Returns `None` if the number of
+    /// indices is unknown.
+    pub fn triangle_count(&self, gltf: &Gltf) -> Option<usize> {
+        Some(self.mode.triangle_count(self.index_count(gltf)?))
+    }
+
+    /// Returns the bounds of this primitive's `POSITION` attribute.
+    ///
+    /// The bounds are read from the accessor's `min` and `max` properties, so
+    /// `None` is returned when the accessor is missing or omits them.
+    pub fn bounding_box(&self, gltf: &Gltf) -> Option<BoundingBox> {
+        let accessor = gltf.accessors.get(self.attribute(&Semantic::Position)?)?;
+        match (accessor.min.as_ref(), accessor.max.as_ref()) {
+            (Some(min), Some(max)) if min.len() >= 3 && max.len() >= 3 => {
+                Some(BoundingBox {
+                    min: [min[0], min[1], min[2]],
+                    max: [max[0], max[1], max[2]],
+                })
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the triangles of this primitive.
+    ///
+    /// `indices` are the values of the `indices` accessor or, for primitives
+    /// rendered without indices, the range `0..count` where `count` is the
+    /// number of vertices.
+    pub fn triangles<I>(&self, indices: I) -> Triangles<I::IntoIter>
+        where I: IntoIterator<Item = u32>
+    {
+        self.mode.triangles(indices)
+    }
+}
+
+/// Reads primitive data through accessor caches, so that accessors shared
+/// between primitives and node instances are decoded once.
+pub(crate) struct Reader<'a> {
+    vectors: accessor::Cache<'a, [f32; 3]>,
+    indices: accessor::Cache<'a, u32>,
+}
+
+impl<'a> Reader<'a> {
+    /// Creates a reader with empty caches.
+    pub fn new(gltf: &'a Gltf, buffers: &'a Buffers) -> Self {
+        Reader {
+            vectors: accessor::Cache::new(gltf, buffers),
+            indices: accessor::Cache::new(gltf, buffers),
+        }
+    }
+
+    /// Reads a three-component attribute of a primitive.
+    pub fn vectors(
+        &mut self,
+        primitive: &Primitive,
+        semantic: &Semantic,
+    ) -> Option<Rc<[[f32; 3]]>> {
+        self.vectors.read(primitive.attribute(semantic)?)
+    }
+
+    /// Reads the vertex indices of a primitive, as `Primitive::read_indices`
+    /// does.
+    pub fn indices(&mut self, primitive: &Primitive, count: usize) -> Option<Rc<[u32]>> {
+        match primitive.indices {
+            Some(ref id) => self.indices.read(id),
+            None => Some((0..count as u32).collect()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Mesh {
+    /// An array of primitives, each defining geometry to be rendered with a
+    /// material.
+    #[serde(default)]
+    pub primitives: Vec<Primitive>,
+
+    /// The user-defined name of this object.
+    ///
+    /// This is not necessarily unique, e.g., a mesh and a buffer could have the
+    /// same name, or two meshes could even have the same name.
+    pub name: Option<String>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+impl Mesh {
+    /// Returns the number of triangles rendered by this mesh's primitives,
+    /// without reading any data.
+    ///
+    /// Primitives whose number of indices is unknown are ignored.
+    pub fn triangle_count(&self, gltf: &Gltf) -> usize {
+        self.primitives
+            .iter()
+            .filter_map(|primitive| primitive.triangle_count(gltf))
+            .sum()
+    }
+
+    /// Returns the union of the bounds of this mesh's primitives.
+    ///
+    /// Primitives without known bounds are ignored. `None` is returned when no
+    /// primitive has known bounds.
+    pub fn bounding_box(&self, gltf: &Gltf) -> Option<BoundingBox> {
+        self.primitives
+            .iter()
+            .filter_map(|primitive| primitive.bounding_box(gltf))
+            .fold(None, |acc, bounds| match acc {
+                Some(acc) => Some(BoundingBox::union(&acc, &bounds)),
+                None => Some(bounds),
+            })
+    }
+}
+
+impl Validate for Mesh {
+    fn validate(&self, gltf: &Gltf, pointer: &Pointer, errors: &mut Vec<Error>) {
+        let uint_indices = gltf.extensions_used.iter()
+            .any(|name| name == ELEMENT_INDEX_UINT);
+        for (i, primitive) in self.primitives.iter().enumerate() {
+            let pointer = pointer.field("primitives").index(i);
+            if let Some(ref id) = primitive.indices {
+                let pointer = pointer.field("indices");
+                if let Some(accessor) = gltf.accessors.get(id) {
+                    let unsigned = match accessor.component_type {
+                        ComponentType::U8 | ComponentType::U16 => true,
+                        ComponentType::U32 => uint_indices,
+                        _ => false,
+                    };
+                    if !unsigned || accessor.kind != Kind::Scalar {
+                        report(errors, Code::InvalidAccessorType, pointer.clone(),
+                               format!("indices must be unsigned SCALAR, found {:?} {:?}",
+                                       accessor.component_type, accessor.kind));
+                    }
+                    if accessor.stride() != accessor.element_size() {
+                        let pointer = Pointer::root().field("accessors").field(id);
+                        report_value(errors, Code::Misaligned, pointer.field("byteStride"),
+                                     accessor.byte_stride,
+                                     format!("{} is used as indices and must be tightly \
+                                              packed", id));
+                    }
+                }
+            }
+            for (name, id) in &primitive.attributes {
+                let pointer = pointer.field("attributes").field(name);
+                let semantic = Semantic::from_attribute(name);
+                if let Semantic::Extra(_) = semantic {
+                    if !semantic.is_application_specific() {
+                        errors.push(Error {
+                            pointer: pointer.clone(),
+                            code: Code::InvalidSemantic,
+                            severity: Severity::Warning,
+                            description: format!("{} is not a standard semantic, and \
+                                                  application-specific semantics must \
+                                                  start with an underscore", name),
+                            value: None,
+                        });
+                    }
+                }
+                if let Some(accessor) = gltf.accessors.get(id) {
+                    if !is_valid_attribute(&semantic, accessor) {
+                        report(errors, Code::InvalidAccessorType, pointer,
+                               format!("{} cannot be {:?} {:?}", name,
+                                       accessor.component_type, accessor.kind));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns whether an accessor's component type and kind are allowed for the
+/// given attribute semantic.
+fn is_valid_attribute(semantic: &Semantic, accessor: &Accessor) -> bool {
+    let float = accessor.component_type == ComponentType::F32;
+    match *semantic {
+        Semantic::Position | Semantic::Normal => float && accessor.kind == Kind::Vec3,
+        Semantic::TexCoord(_) => float && accessor.kind == Kind::Vec2,
+        Semantic::Color(_) => {
+            float && (accessor.kind == Kind::Vec3 || accessor.kind == Kind::Vec4)
+        },
+        Semantic::Joint(_) => {
+            let joint_type = matches!(accessor.component_type,
+                                      ComponentType::F32
+                                      | ComponentType::U8
+                                      | ComponentType::U16);
+            joint_type && accessor.kind == Kind::Vec4
+        },
+        Semantic::Weight(_) => float && accessor.kind == Kind::Vec4,
+        Semantic::Extra(_) => true,
+    }
+}
+
+/// An axis-aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBox {
+    /// The minimum corner.
+    pub min: [f32; 3],
+
+    /// The maximum corner.
+    pub max: [f32; 3],
+}
+
+impl BoundingBox {
+    /// Computes the bounds of a set of points.
+    ///
+    /// Returns `None` when there are no points.
+    pub fn from_points<I>(points: I) -> Option<BoundingBox>
+        where I: IntoIterator<Item = [f32; 3]>
+    {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = BoundingBox { min: first, max: first };
+        for point in points {
+            bounds.extend(point);
+        }
+        Some(bounds)
+    }
+
+    /// Grows the bounds to contain the given point.
+    pub fn extend(&mut self, point: [f32; 3]) {
+        for (i, &value) in point.iter().enumerate() {
+            self.min[i] = self.min[i].min(value);
+            self.max[i] = self.max[i].max(value);
+        }
+    }
+
+    /// Returns the smallest bounds containing both `a` and `b`.
+    pub fn union(a: &BoundingBox, b: &BoundingBox) -> BoundingBox {
+        let mut bounds = *a;
+        bounds.extend(b.min);
+        bounds.extend(b.max);
+        bounds
+    }
+
+    /// Returns the center point of the bounds.
+    pub fn center(&self) -> [f32; 3] {
+        math::scale(math::add(self.min, self.max), 0.5)
+    }
+
+    /// Returns the eight corners of the bounds.
+    pub fn corners(&self) -> [[f32; 3]; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            [a[0], a[1], a[2]],
+            [b[0], a[1], a[2]],
+            [a[0], b[1], a[2]],
+            [b[0], b[1], a[2]],
+            [a[0], a[1], b[2]],
+            [b[0], a[1], b[2]],
+            [a[0], b[1], b[2]],
+            [b[0], b[1], b[2]],
+        ]
+    }
+
+    /// Returns the bounds of this box after transformation by a column-major
+    /// matrix.
+    pub fn transform(&self, matrix: &[f32; 16]) -> BoundingBox {
+        let corners = self.corners();
+        let transformed = corners.iter().map(|&c| math::transform_point(matrix, c));
+        BoundingBox::from_points(transformed).unwrap()
+    }
+}
+
+/// Computes smooth per-vertex normals for an indexed triangle list.
+///
+/// The normal of each vertex is the area-weighted average of the normals of
+/// the triangles sharing it. Vertices not referenced by any triangle are given
+/// the zero vector. Triangles with an index out of range of `positions` are
+/// skipped.
+pub fn compute_normals<I>(positions: &[[f32; 3]], triangles: I) -> Vec<[f32; 3]>
+    where I: IntoIterator<Item = [u32; 3]>
+{
+    let mut normals = vec![[0.0; 3]; positions.len()];
+    for [a, b, c] in triangles {
+        let (a, b, c) = (a as usize, b as usize, c as usize);
+        let face = match (positions.get(a), positions.get(b), positions.get(c)) {
+            (Some(&pa), Some(&pb), Some(&pc)) => face_normal(pa, pb, pc),
+            _ => continue,
+        };
+        for &i in &[a, b, c] {
+            normals[i] = math::add(normals[i], face);
+        }
+    }
+    for normal in &mut normals {
+        *normal = math::normalize(*normal);
+    }
+    normals
+}
+
+/// Computes flat normals for an indexed triangle list.
+///
+/// Since flat shaded vertices cannot be shared between faces, the triangles are
+/// unwelded: the returned positions and normals form a non-indexed triangle
+/// list with three vertices per input triangle. Triangles with an index out of
+/// range of `positions` are skipped.
+pub fn compute_flat_normals<I>(
+    positions: &[[f32; 3]],
+    triangles: I,
+) -> (Vec<[f32; 3]>, Vec<[f32; 3]>)
+    where I: IntoIterator<Item = [u32; 3]>
+{
+    let mut flat_positions = Vec::new();
+    let mut flat_normals = Vec::new();
+    for [a, b, c] in triangles {
+        let get = |i: u32| positions.get(i as usize).cloned();
+        let vertices = match (get(a), get(b), get(c)) {
+            (Some(a), Some(b), Some(c)) => [a, b, c],
+            _ => continue,
+        };
+        let face = face_normal(vertices[0], vertices[1], vertices[2]);
+        let normal = math::normalize(face);
+        flat_positions.extend_from_slice(&vertices);
+        flat_normals.extend_from_slice(&[normal; 3]);
+    }
+    (flat_positions, flat_normals)
+}
+
+/// Returns the normal of a counter-clockwise triangle, scaled by twice its area.
+fn face_normal(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> [f32; 3] {
+    math::cross(math::sub(b, a), math::sub(c, a))
+}
+
+impl_references!(Primitive {
+    ids {
+        attributes: "attributes" => accessors,
+        indices: "indices" => accessors,
+        material: "material" => materials,
+    }
+    nested {}
+});
+
+impl_references!(Mesh {
+    ids {}
+    nested { primitives: "primitives", }
+});
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+    use v1::validation::Severity;
+
+    fn triangles(mode: Mode, count: u32) -> Vec<[u32; 3]> {
+        mode.triangles(0..count).collect()
+    }
+
+    #[test]
+    fn it_narrows_indices_that_fit() {
+        assert_eq!(Some(vec![0, 65535]), narrow_indices(&[0, 65535]));
+        assert_eq!(None, narrow_indices(&[0, 65536]));
+    }
+
+    #[test]
+    fn it_splits_triangle_lists() {
+        assert_eq!(vec![[0, 1, 2], [3, 4, 5]], triangles(Mode::Triangles, 7));
+    }
+
+    #[test]
+    fn it_converts_triangle_strips() {
+        assert_eq!(vec![[0, 1, 2], [2, 1, 3], [2, 3, 4]],
+                   triangles(Mode::TriangleStrip, 5));
+        assert!(triangles(Mode::TriangleStrip, 2).is_empty());
+    }
+
+    #[test]
+    fn it_converts_triangle_fans() {
+        assert_eq!(vec![[0, 1, 2], [0, 2, 3], [0, 3, 4]],
+                   triangles(Mode::TriangleFan, 5));
+    }
+
+    #[test]
+    fn it_ignores_points_and_lines() {
+        assert!(triangles(Mode::Points, 6).is_empty());
+        assert!(triangles(Mode::LineStrip, 6).is_empty());
+    }
+
+    #[test]
+    fn it_resolves_indices() {
+        let primitive = Primitive {
+            mode: Mode::TriangleFan,
+            ..Default::default()
+        };
+        let tris: Vec<_> = primitive.triangles(vec![7, 3, 9, 4]).collect();
+        assert_eq!(vec![[7, 3, 9], [7, 9, 4]], tris);
+    }
+
+    #[test]
+    fn it_counts_without_reading_data() {
+        extern crate serde_json;
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "positions": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 5, "type": "VEC3"
+                },
+                "indices": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5123, "count": 9, "type": "SCALAR"
+                }
+            },
+            "meshes": {
+                "mesh": {
+                    "primitives": [
+                        {
+                            "attributes": { "POSITION": "positions" },
+                            "indices": "indices",
+                            "material": "material"
+                        },
+                        {
+                            "attributes": { "POSITION": "positions" },
+                            "material": "material",
+                            "mode": 5
+                        },
+                        { "attributes": {}, "material": "material" }
+                    ]
+                }
+            }
+        }"#).unwrap();
+        let mesh = &gltf.meshes["mesh"];
+        let primitive = &mesh.primitives[0];
+        assert_eq!(Some(5), primitive.vertex_count(&gltf));
+        assert_eq!(Some(9), primitive.index_count(&gltf));
+        assert_eq!(Some(3), primitive.triangle_count(&gltf));
+        assert_eq!(Some(5), mesh.primitives[1].index_count(&gltf));
+        assert_eq!(Some(3), mesh.primitives[1].triangle_count(&gltf));
+        assert_eq!(None, mesh.primitives[2].triangle_count(&gltf));
+        assert_eq!(6, mesh.triangle_count(&gltf));
+    }
+
+    #[test]
+    fn it_parses_attribute_semantics() {
+        let semantic = Semantic::from_attribute;
+        assert_eq!(Semantic::Position, semantic("POSITION"));
+        assert_eq!(Semantic::TexCoord(3), semantic("TEXCOORD_3"));
+        assert_eq!(Semantic::Joint(0), semantic("JOINT"));
+        assert_eq!(Semantic::Joint(1), semantic("JOINT_1"));
+        assert_eq!(Semantic::Weight(2), semantic("WEIGHTS_2"));
+        assert_eq!(Semantic::Extra("_ID_X".to_string()), semantic("_ID_X"));
+        assert_eq!(Semantic::Extra("POSITION_1".to_string()), semantic("POSITION_1"));
+    }
+
+    #[test]
+    fn it_round_trips_semantics() {
+        let semantics = [
+            Semantic::Position,
+            Semantic::Normal,
+            Semantic::TexCoord(3),
+            Semantic::Color(0),
+            Semantic::Joint(1),
+            Semantic::Weight(12),
+            Semantic::Extra("_BATCHID".to_string()),
+        ];
+        for semantic in &semantics {
+            assert_eq!(Ok(semantic.clone()), semantic.to_string().parse());
+        }
+        assert_eq!("TEXCOORD_3", Semantic::TexCoord(3).to_string());
+        assert_eq!(Ok(Semantic::Joint(0)), "JOINT".parse());
+        assert_eq!(Ok(Semantic::Weight(2)), "WEIGHTS_2".parse());
+        assert_eq!(Err(ParseSemanticError::Empty), "".parse::<Semantic>());
+        for name in &["TEXCOORD_", "TEXCOORD_x", "TEXCOORD_01", "POSITION_1", "COLOR_-1"] {
+            let error = ParseSemanticError::SetIndex(name.to_string());
+            assert_eq!(Err(error), name.parse::<Semantic>());
+        }
+    }
+
+    #[test]
+    fn it_writes_and_reads_application_specific_attributes() {
+        use v1::accessor::Values;
+        use v1::builder::Builder;
+
+        let mut gltf = Gltf::default();
+        let mut buffers = Buffers::new();
+        let batch_id = Semantic::Extra("_BATCHID".to_string());
+        assert!(batch_id.is_application_specific());
+        assert!(!Semantic::Extra("TANGENT".to_string()).is_application_specific());
+
+        let values = Values::U16(vec![0, 0, 1]);
+        let id = Builder::new(&mut gltf, &mut buffers, "buffer")
+            .accessor_values("batch_ids", Kind::Scalar, &values, None);
+        let mut primitive = Primitive::default();
+        primitive.set_attribute(&batch_id, &id);
+        primitive.attributes.insert("JOINT".to_string(), "j0".to_string());
+        primitive.set_attribute(&Semantic::Joint(0), "j1");
+        assert_eq!(Some(&id), primitive.attributes.get("_BATCHID"));
+        assert_eq!(Some("j1"), primitive.attribute(&Semantic::Joint(0)));
+        assert_eq!(2, primitive.attributes.len());
+        assert_eq!(Some(values), primitive.read_attribute(&gltf, &buffers, &batch_id));
+
+        primitive.attributes.remove("JOINT");
+        primitive.attributes.insert("TANGENT".to_string(), id);
+        gltf.meshes.insert("mesh".to_string(), Mesh {
+            primitives: vec![primitive],
+            ..Default::default()
+        });
+        let warnings: Vec<String> = gltf.validate()
+            .into_iter()
+            .filter(|error| error.code == Code::InvalidSemantic)
+            .map(|error| error.pointer.to_string())
+            .collect();
+        assert_eq!(vec!["/meshes/mesh/primitives/0/attributes/TANGENT"], warnings);
+    }
+
+    #[test]
+    fn it_finds_attributes_by_semantic() {
+        let mut primitive = Primitive::default();
+        primitive.attributes.insert("JOINT".to_string(), "j0".to_string());
+        primitive.attributes.insert("JOINT_1".to_string(), "j1".to_string());
+        primitive.attributes.insert("WEIGHT_1".to_string(), "w1".to_string());
+        assert_eq!(Some("j0"), primitive.attribute(&Semantic::Joint(0)));
+        assert_eq!(Some("j1"), primitive.attribute(&Semantic::Joint(1)));
+        assert_eq!(Some("w1"), primitive.attribute(&Semantic::Weight(1)));
+        assert_eq!(None, primitive.attribute(&Semantic::Weight(0)));
+    }
+
+    #[test]
+    fn it_reads_vertices() {
+        extern crate serde_json;
+        let data = r#"{
+    "accessors": {
+        "positions": {
+            "bufferView": "view",
+            "byteOffset": 0,
+            "componentType": 5126,
+            "count": 2,
+            "type": "VEC3"
+        },
+        "colors": {
+            "bufferView": "view",
+            "byteOffset": 24,
+            "componentType": 5121,
+            "count": 2,
+            "type": "VEC3"
+        }
+    },
+    "bufferViews": {
+        "view": { "buffer": "buffer", "byteOffset": 0, "byteLength": 30 }
+    },
+    "meshes": {
+        "mesh": {
+            "primitives": [{
+                "attributes": { "POSITION": "positions", "COLOR_0": "colors" },
+                "material": "material"
+            }]
+        }
+    }
+}"#;
+        let gltf: Gltf = serde_json::from_str(data).unwrap();
+        let mut bytes = Vec::new();
+        for x in &[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0] {
+            bytes.extend_from_slice(&x.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[10, 20, 30, 40, 50, 60]);
+        let mut buffers = Buffers::new();
+        buffers.insert("buffer".to_string(), bytes.into());
+
+        let primitive = &gltf.meshes["mesh"].primitives[0];
+        let options = VertexOptions::default();
+        let vertices: Vec<_> = primitive
+            .read_vertices(&gltf, &buffers, &options)
+            .unwrap()
+            .collect();
+        assert_eq!(2, vertices.len());
+        assert_eq!([4.0, 5.0, 6.0], vertices[1].position);
+        let color = [40.0 / 255.0, 50.0 / 255.0, 60.0 / 255.0, 1.0];
+        assert_eq!(Some(color), vertices[1].color);
+        assert_eq!(None, vertices[1].normal);
+
+        let mut signed: Gltf = serde_json::from_str(data).unwrap();
+        signed.accessors.get_mut("colors").unwrap().component_type = ComponentType::I8;
+        let primitive = &signed.meshes["mesh"].primitives[0];
+        let vertex = primitive.read_vertices(&signed, &buffers, &options).unwrap().next();
+        assert_eq!(None, vertex.unwrap().color);
+
+        let primitive = &gltf.meshes["mesh"].primitives[0];
+        let options = VertexOptions { color: None, ..options };
+        let vertex = primitive
+            .read_vertices(&gltf, &buffers, &options)
+            .unwrap()
+            .next()
+            .unwrap();
+        assert_eq!(None, vertex.color);
+
+        buffers.clear();
+        assert!(primitive.read_vertices(&gltf, &buffers, &options).is_none());
+    }
+
+    #[test]
+    fn it_computes_smooth_normals() {
+        // Two triangles folded along the y axis, plus an unused vertex.
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 0.0, -1.0],
+            [5.0, 5.0, 5.0],
+        ];
+        let triangles = vec![[0, 2, 1], [0, 1, 3], [4, 0, 9]];
+        let normals = compute_normals(&positions, triangles);
+        let s = 0.5f32.sqrt();
+        assert_eq!([0.0, 0.0, 1.0], normals[2]);
+        assert_eq!([-1.0, 0.0, 0.0], normals[3]);
+        assert!((normals[0][0] + s).abs() < 1e-6);
+        assert!((normals[0][2] - s).abs() < 1e-6);
+        assert_eq!([0.0, 0.0, 0.0], normals[4]);
+    }
+
+    #[test]
+    fn it_computes_flat_normals() {
+        let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let (flat_positions, normals) =
+            compute_flat_normals(&positions, vec![[0, 1, 2], [0, 1, 3]]);
+        assert_eq!(positions.to_vec(), flat_positions);
+        assert_eq!(vec![[0.0, 0.0, 1.0]; 3], normals);
+    }
+
+    #[test]
+    fn it_validates_accessor_types() {
+        let data = r#"{
+    "accessors": {
+        "positions": {
+            "bufferView": "view",
+            "byteOffset": 0,
+            "componentType": 5126,
+            "count": 3,
+            "type": "VEC3"
+        },
+        "float_indices": {
+            "bufferView": "view",
+            "byteOffset": 2,
+            "componentType": 5126,
+            "count": 3,
+            "type": "SCALAR"
+        }
+    },
+    "bufferViews": {
+        "view": { "buffer": "buffer", "byteOffset": 0, "byteLength": 36 }
+    },
+    "meshes": {
+        "mesh": {
+            "primitives": [
+                {
+                    "attributes": {
+                        "POSITION": "positions",
+                        "TEXCOORD_0": "positions",
+                        "NORMAL": "missing"
+                    },
+                    "indices": "float_indices",
+                    "material": "material"
+                }
+            ]
+        }
+    }
+}"#;
+        let gltf: Gltf = serde_json::from_str(data).unwrap();
+        let mut pointers: Vec<String> = gltf.validate()
+            .into_iter()
+            .filter(|error| error.severity == Severity::Error)
+            .map(|error| error.pointer.to_string())
+            .collect();
+        pointers.sort();
+        assert_eq!(vec![
+            "/accessors/float_indices/byteOffset",
+            "/bufferViews/view/buffer",
+            "/meshes/mesh/primitives/0/attributes/NORMAL",
+            "/meshes/mesh/primitives/0/attributes/TEXCOORD_0",
+            "/meshes/mesh/primitives/0/indices",
+            "/meshes/mesh/primitives/0/material",
+        ], pointers);
+    }
+
+    #[test]
+    fn it_validates_index_accessors() {
+        let mut gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "indices": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5125, "count": 3, "type": "SCALAR"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteLength": 12 }
+            },
+            "meshes": {
+                "mesh": { "primitives": [{ "indices": "indices", "material": "m" }] }
+            }
+        }"#).unwrap();
+        let invalid = |gltf: &Gltf| {
+            gltf.validate().into_iter().any(|error| {
+                error.code == Code::InvalidAccessorType
+                    && error.pointer.as_str() == "/meshes/mesh/primitives/0/indices"
+            })
+        };
+        assert!(invalid(&gltf));
+        gltf.extensions_used.push(ELEMENT_INDEX_UINT.to_string());
+        assert!(!invalid(&gltf));
+
+        gltf.accessors.get_mut("indices").unwrap().byte_stride = 8;
+        let errors: Vec<_> = gltf.validate()
+            .into_iter()
+            .filter(|error| error.code == Code::Misaligned)
+            .collect();
+        assert_eq!(1, errors.len());
+        assert_eq!("/accessors/indices/byteStride", errors[0].pointer.as_str());
+        assert_eq!(Some(8.into()), errors[0].value);
+    }
+}
@@ -0,0 +1,484 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Mesh simplification by iterative edge collapse.
+//!
+//! The implementation follows Garland and Heckbert's quadric error metrics.
+//! Open boundaries are preserved by constraint planes, and collapses that
+//! would flip a neighbouring triangle are rejected.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use v1::Gltf;
+use v1::buffer::{Buffers, Target};
+use v1::builder::Builder;
+use v1::math;
+use v1::mesh::{Mode, PackedAttribute, Primitive, Semantic};
+
+/// Weight of the constraint planes that keep open boundaries in place.
+const BOUNDARY_WEIGHT: f64 = 10.0;
+
+/// The output of `decimate`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Simplified {
+    /// The remaining vertex positions.
+    pub positions: Vec<[f32; 3]>,
+
+    /// Triangle list indices into `positions`.
+    pub indices: Vec<u32>,
+
+    /// For each remaining vertex, the index of the input vertex it originates
+    /// from, so that other vertex attributes can be carried over.
+    pub source: Vec<u32>,
+}
+
+/// Reduces an indexed triangle list to at most `target` triangles, or as close
+/// as possible without degrading the mesh.
+///
+/// Degenerate triangles and triangles with an index out of range of
+/// `positions` are dropped.
+pub fn decimate(positions: &[[f32; 3]], indices: &[u32], target: usize) -> Simplified {
+    let mut mesh = Mesh::new(positions, indices);
+    let mut heap = BinaryHeap::new();
+    for a in 0..mesh.positions.len() {
+        for b in mesh.neighbours(a) {
+            if a < b {
+                heap.push(mesh.collapse(a, b));
+            }
+        }
+    }
+    while mesh.live > target {
+        let collapse = match heap.pop() {
+            Some(collapse) => collapse,
+            None => break,
+        };
+        if !mesh.is_current(&collapse) || mesh.flips(&collapse) {
+            continue;
+        }
+        mesh.apply(&collapse);
+        let a = collapse.keep;
+        for b in mesh.neighbours(a) {
+            heap.push(mesh.collapse(a, b));
+        }
+    }
+    mesh.compact()
+}
+
+/// Decimates a triangle primitive to at most `target` triangles, returning a
+/// copy of it that references new accessors named after `id` and appended to
+/// `buffer`.
+///
+/// Strips and fans are decimated as triangle lists. The new `POSITION`
+/// accessor holds the simplified positions, and every other attribute is
+/// carried over from the input vertex each remaining vertex originates from.
+/// Returns `None` if the primitive does not render triangles, has no
+/// `POSITION` attribute, or if its data cannot be read from `buffers`.
+pub fn decimate_primitive(
+    gltf: &mut Gltf,
+    buffers: &mut Buffers,
+    buffer: &str,
+    primitive: &Primitive,
+    id: &str,
+    target: usize,
+) -> Option<Primitive> {
+    match primitive.mode {
+        Mode::Triangles | Mode::TriangleStrip | Mode::TriangleFan => {},
+        _ => return None,
+    }
+    let positions: Vec<[f32; 3]> = {
+        let accessor = gltf.accessors.get(primitive.attribute(&Semantic::Position)?)?;
+        accessor.view(gltf, buffers)?.iter().collect()
+    };
+    let indices: Vec<u32> = primitive.triangles(primitive.read_indices(gltf, buffers)?)
+        .flatten()
+        .collect();
+    let attributes = PackedAttribute::read_all(gltf, buffers, primitive, positions.len())?;
+    let simplified = decimate(&positions, &indices, target);
+
+    let mut builder = Builder::new(gltf, buffers, buffer);
+    let mut decimated = Primitive {
+        attributes: HashMap::new(),
+        mode: Mode::Triangles,
+        ..primitive.clone()
+    };
+    for attribute in &attributes {
+        let new_id = if Semantic::from_attribute(&attribute.name) == Semantic::Position {
+            builder.accessor(&format!("{}_{}", id, attribute.name), &simplified.positions,
+                             Some(Target::ArrayBuffer))
+        } else {
+            let sources = simplified.source.iter().map(|&i| i as usize);
+            attribute.write(&mut builder, id, sources)
+        };
+        decimated.attributes.insert(attribute.name.clone(), new_id);
+    }
+    let indices = builder.indices(&format!("{}_indices", id), &simplified.indices);
+    decimated.indices = Some(indices);
+    Some(decimated)
+}
+
+/// A symmetric 4x4 matrix measuring squared distance to a set of planes.
+#[derive(Clone, Copy, Debug, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    /// Returns the quadric of the plane `ax + by + cz + d = 0`, scaled by
+    /// `weight`.
+    fn plane(n: [f32; 3], d: f32, weight: f64) -> Quadric {
+        let (a, b, c, d) = (n[0] as f64, n[1] as f64, n[2] as f64, d as f64);
+        let q = [
+            a * a, a * b, a * c, a * d,
+            b * b, b * c, b * d,
+            c * c, c * d,
+            d * d,
+        ];
+        let mut scaled = [0.0; 10];
+        for (s, x) in scaled.iter_mut().zip(q.iter()) {
+            *s = x * weight;
+        }
+        Quadric(scaled)
+    }
+
+    fn add(&self, other: &Quadric) -> Quadric {
+        let mut sum = [0.0; 10];
+        for (i, s) in sum.iter_mut().enumerate() {
+            *s = self.0[i] + other.0[i];
+        }
+        Quadric(sum)
+    }
+
+    fn error(&self, p: [f32; 3]) -> f64 {
+        let q = &self.0;
+        let (x, y, z) = (p[0] as f64, p[1] as f64, p[2] as f64);
+        q[0] * x * x + 2.0 * q[1] * x * y + 2.0 * q[2] * x * z + 2.0 * q[3] * x
+            + q[4] * y * y + 2.0 * q[5] * y * z + 2.0 * q[6] * y
+            + q[7] * z * z + 2.0 * q[8] * z
+            + q[9]
+    }
+}
+
+/// A candidate edge collapse, ordered so that the cheapest is popped first.
+#[derive(Clone, Copy, Debug)]
+struct Collapse {
+    cost: f64,
+    keep: usize,
+    remove: usize,
+    position: [f32; 3],
+    stamps: (u32, u32),
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Collapse) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Collapse) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    fn cmp(&self, other: &Collapse) -> Ordering {
+        other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
+    }
+}
+
+struct Mesh {
+    positions: Vec<[f32; 3]>,
+    quadrics: Vec<Quadric>,
+    triangles: Vec<[usize; 3]>,
+    alive: Vec<bool>,
+    /// Triangles adjacent to each vertex, possibly including dead ones.
+    adjacency: Vec<Vec<usize>>,
+    /// Incremented whenever a vertex changes, invalidating queued collapses.
+    stamps: Vec<u32>,
+    /// The number of live triangles.
+    live: usize,
+}
+
+impl Mesh {
+    fn new(positions: &[[f32; 3]], indices: &[u32]) -> Mesh {
+        let triangles: Vec<[usize; 3]> = indices
+            .chunks(3)
+            .filter(|t| t.len() == 3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+            .filter(|t| t[0] != t[1] && t[1] != t[2] && t[2] != t[0])
+            .filter(|t| t.iter().all(|&v| v < positions.len()))
+            .collect();
+        let mut quadrics = vec![Quadric::default(); positions.len()];
+        let mut adjacency = vec![Vec::new(); positions.len()];
+        for (i, t) in triangles.iter().enumerate() {
+            let n = normal(positions[t[0]], positions[t[1]], positions[t[2]]);
+            let q = Quadric::plane(n, -math::dot(n, positions[t[0]]), 1.0);
+            for &v in t {
+                quadrics[v] = quadrics[v].add(&q);
+                adjacency[v].push(i);
+            }
+        }
+
+        // Constrain each boundary edge, i.e. an edge used by one triangle, to
+        // the plane through it perpendicular to its triangle.
+        let mut edges = Vec::new();
+        for t in &triangles {
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                edges.push((a.min(b), a.max(b), a, b, *t));
+            }
+        }
+        edges.sort_by_key(|e| (e.0, e.1));
+        for (i, e) in edges.iter().enumerate() {
+            let shared = (i > 0 && (edges[i - 1].0, edges[i - 1].1) == (e.0, e.1))
+                || edges.get(i + 1).is_some_and(|n| (n.0, n.1) == (e.0, e.1));
+            if shared {
+                continue;
+            }
+            let (a, b, t) = (e.2, e.3, e.4);
+            let face = normal(positions[t[0]], positions[t[1]], positions[t[2]]);
+            let edge = math::sub(positions[b], positions[a]);
+            let n = math::normalize(math::cross(edge, face));
+            let q = Quadric::plane(n, -math::dot(n, positions[a]), BOUNDARY_WEIGHT);
+            quadrics[a] = quadrics[a].add(&q);
+            quadrics[b] = quadrics[b].add(&q);
+        }
+
+        Mesh {
+            positions: positions.to_vec(),
+            quadrics,
+            live: triangles.len(),
+            alive: vec![true; triangles.len()],
+            triangles,
+            adjacency,
+            stamps: vec![0; positions.len()],
+        }
+    }
+
+    /// Returns the vertices sharing a live triangle with `v`.
+    fn neighbours(&self, v: usize) -> Vec<usize> {
+        let mut neighbours: Vec<usize> = self.adjacency[v]
+            .iter()
+            .filter(|&&t| self.alive[t])
+            .flat_map(|&t| self.triangles[t].to_vec())
+            .filter(|&n| n != v)
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+
+    /// Returns the cheapest collapse of the edge between `a` and `b`.
+    fn collapse(&self, a: usize, b: usize) -> Collapse {
+        let q = self.quadrics[a].add(&self.quadrics[b]);
+        let (pa, pb) = (self.positions[a], self.positions[b]);
+        let midpoint = math::scale(math::add(pa, pb), 0.5);
+        let mut best = Collapse {
+            cost: q.error(midpoint),
+            keep: a,
+            remove: b,
+            position: midpoint,
+            stamps: (self.stamps[a], self.stamps[b]),
+        };
+        for &position in &[pa, pb] {
+            let cost = q.error(position);
+            if cost < best.cost {
+                best.cost = cost;
+                best.position = position;
+            }
+        }
+        best
+    }
+
+    fn is_current(&self, collapse: &Collapse) -> bool {
+        let (a, b) = (collapse.keep, collapse.remove);
+        (self.stamps[a], self.stamps[b]) == collapse.stamps
+            && self.adjacency[b].iter().any(|&t| self.alive[t])
+    }
+
+    /// Returns `true` if the collapse would flip a surviving triangle.
+    fn flips(&self, collapse: &Collapse) -> bool {
+        let (a, b) = (collapse.keep, collapse.remove);
+        for &v in &[a, b] {
+            for &t in &self.adjacency[v] {
+                let tri = self.triangles[t];
+                if !self.alive[t] || (tri.contains(&a) && tri.contains(&b)) {
+                    continue;
+                }
+                let before = |k: usize| self.positions[tri[k]];
+                let after = |k: usize| if tri[k] == v {
+                    collapse.position
+                } else {
+                    self.positions[tri[k]]
+                };
+                let n0 = normal(before(0), before(1), before(2));
+                let n1 = normal(after(0), after(1), after(2));
+                if math::dot(n0, n1) <= 0.0 {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn apply(&mut self, collapse: &Collapse) {
+        let (a, b) = (collapse.keep, collapse.remove);
+        self.positions[a] = collapse.position;
+        self.quadrics[a] = self.quadrics[a].add(&self.quadrics[b]);
+        self.stamps[a] += 1;
+        self.stamps[b] += 1;
+        let moved = ::std::mem::take(&mut self.adjacency[b]);
+        for t in moved {
+            if !self.alive[t] {
+                continue;
+            }
+            let tri = &mut self.triangles[t];
+            for v in tri.iter_mut() {
+                if *v == b {
+                    *v = a;
+                }
+            }
+            if tri[0] == tri[1] || tri[1] == tri[2] || tri[2] == tri[0] {
+                self.alive[t] = false;
+                self.live -= 1;
+            } else {
+                self.adjacency[a].push(t);
+            }
+        }
+        let alive = &self.alive;
+        self.adjacency[a].retain(|&t| alive[t]);
+    }
+
+    /// Returns the live triangles, keeping only referenced vertices in their
+    /// original order.
+    fn compact(&self) -> Simplified {
+        let mut output = Simplified::default();
+        let mut remap = vec![None; self.positions.len()];
+        for (t, tri) in self.triangles.iter().enumerate() {
+            if self.alive[t] {
+                for &v in tri {
+                    remap[v] = Some(0);
+                }
+            }
+        }
+        for (v, index) in remap.iter_mut().enumerate() {
+            if index.is_some() {
+                *index = Some(output.positions.len() as u32);
+                output.positions.push(self.positions[v]);
+                output.source.push(v as u32);
+            }
+        }
+        for (t, tri) in self.triangles.iter().enumerate() {
+            if self.alive[t] {
+                output.indices.extend(tri.iter().map(|&v| remap[v].unwrap()));
+            }
+        }
+        output
+    }
+}
+
+/// Returns the unit normal of a counter-clockwise triangle.
+fn normal(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> [f32; 3] {
+    math::normalize(math::cross(math::sub(b, a), math::sub(c, a)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Returns an `n` by `n` grid of quads on the z = 0 plane.
+    fn grid(n: u32) -> (Vec<[f32; 3]>, Vec<u32>) {
+        let mut positions = Vec::new();
+        for y in 0..n + 1 {
+            for x in 0..n + 1 {
+                positions.push([x as f32, y as f32, 0.0]);
+            }
+        }
+        let mut indices = Vec::new();
+        for y in 0..n {
+            for x in 0..n {
+                let i = y * (n + 1) + x;
+                indices.extend_from_slice(&[i, i + 1, i + n + 2, i, i + n + 2, i + n + 1]);
+            }
+        }
+        (positions, indices)
+    }
+
+    fn area(positions: &[[f32; 3]], indices: &[u32]) -> f32 {
+        indices
+            .chunks(3)
+            .map(|t| {
+                let p = |i: u32| positions[i as usize];
+                let (a, b, c) = (p(t[0]), p(t[1]), p(t[2]));
+                math::cross(math::sub(b, a), math::sub(c, a))[2] * 0.5
+            })
+            .sum()
+    }
+
+    #[test]
+    fn it_decimates_a_plane() {
+        let (positions, indices) = grid(8);
+        let simplified = decimate(&positions, &indices, 16);
+        let triangles = simplified.indices.len() / 3;
+        assert!((2..=16).contains(&triangles));
+        assert_eq!(simplified.positions.len(), simplified.source.len());
+        assert!(simplified.positions.iter().all(|p| p[2] == 0.0));
+        // Boundaries are preserved and no triangle is flipped.
+        assert!((area(&simplified.positions, &simplified.indices) - 64.0).abs() < 1e-3);
+        assert!(simplified
+            .indices
+            .chunks(3)
+            .all(|t| area(&simplified.positions, t) > 0.0));
+    }
+
+    #[test]
+    fn it_keeps_meshes_under_target() {
+        let (positions, indices) = grid(2);
+        let simplified = decimate(&positions, &indices, 100);
+        assert_eq!(indices.len(), simplified.indices.len());
+        assert_eq!(positions, simplified.positions);
+    }
+
+    #[test]
+    fn it_drops_out_of_range_triangles() {
+        let (positions, mut indices) = grid(2);
+        indices.extend_from_slice(&[0, 1, 99]);
+        let simplified = decimate(&positions, &indices, 100);
+        assert_eq!(&indices[..indices.len() - 3], &simplified.indices[..]);
+    }
+
+    #[test]
+    fn it_decimates_primitives() {
+        let mut gltf = Gltf::default();
+        let mut buffers = Buffers::new();
+        let (positions, indices) = grid(4);
+        let ids: Vec<f32> = (0..positions.len()).map(|i| i as f32).collect();
+        let primitive = {
+            let mut builder = Builder::new(&mut gltf, &mut buffers, "buffer");
+            let mut primitive = Primitive::default();
+            primitive.attributes.insert(
+                "POSITION".to_string(), builder.accessor("positions", &positions, None));
+            primitive.attributes.insert(
+                "_ID".to_string(), builder.accessor("ids", &ids, None));
+            primitive.indices = Some(builder.indices("indices", &indices));
+            primitive
+        };
+        let decimated = decimate_primitive(&mut gltf, &mut buffers, "buffer", &primitive,
+                                           "decimated", 2).unwrap();
+        let simplified = decimate(&positions, &indices, 2);
+        assert_eq!(Some(simplified.indices), decimated.read_indices(&gltf, &buffers));
+        let view = |name: &str| {
+            gltf.accessors[&decimated.attributes[name]].view(&gltf, &buffers).unwrap()
+        };
+        let ids: Vec<f32> = view("_ID").iter().collect();
+        let expected: Vec<f32> = simplified.source.iter().map(|&i| i as f32).collect();
+        assert_eq!(expected, ids);
+        let positions: Vec<[f32; 3]> = view("POSITION").iter().collect();
+        assert_eq!(simplified.positions, positions);
+    }
+}
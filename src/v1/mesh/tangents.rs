@@ -0,0 +1,121 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Tangent generation for primitives that provide no tangents of their own.
+//!
+//! Tangents follow the MikkTSpace conventions: they are orthogonalized
+//! against the vertex normal, and the `w` component holds the handedness so
+//! that `bitangent = cross(normal, tangent.xyz) * tangent.w`.
+
+use v1::math;
+
+/// Generates per-vertex tangents for an indexed triangle list.
+///
+/// `positions`, `normals`, and `tex_coords` are the vertex attributes, which
+/// should have the same length; if they do not, only as many vertices as the
+/// shortest holds are used, and a tangent is returned for each of them.
+/// Triangles with an index out of that range are skipped. Each vertex tangent
+/// is the sum of the tangents of the triangles sharing it. Vertices whose
+/// triangles have degenerate texture coordinates receive an arbitrary tangent
+/// perpendicular to their normal.
+pub fn generate<I>(
+    positions: &[[f32; 3]],
+    normals: &[[f32; 3]],
+    tex_coords: &[[f32; 2]],
+    triangles: I,
+) -> Vec<[f32; 4]>
+    where I: IntoIterator<Item = [u32; 3]>
+{
+    let count = positions.len().min(normals.len()).min(tex_coords.len());
+    let mut tangents = vec![[0.0; 3]; count];
+    let mut bitangents = vec![[0.0; 3]; count];
+    for [a, b, c] in triangles {
+        let (a, b, c) = (a as usize, b as usize, c as usize);
+        if a >= count || b >= count || c >= count {
+            continue;
+        }
+        let e1 = math::sub(positions[b], positions[a]);
+        let e2 = math::sub(positions[c], positions[a]);
+        let (uv0, uv1, uv2) = (tex_coords[a], tex_coords[b], tex_coords[c]);
+        let (du1, dv1) = (uv1[0] - uv0[0], uv1[1] - uv0[1]);
+        let (du2, dv2) = (uv2[0] - uv0[0], uv2[1] - uv0[1]);
+        let det = du1 * dv2 - du2 * dv1;
+        if det == 0.0 {
+            continue;
+        }
+        let r = 1.0 / det;
+        let sdir = math::scale(math::sub(math::scale(e1, dv2), math::scale(e2, dv1)), r);
+        let tdir = math::scale(math::sub(math::scale(e2, du1), math::scale(e1, du2)), r);
+        for &i in &[a, b, c] {
+            tangents[i] = math::add(tangents[i], sdir);
+            bitangents[i] = math::add(bitangents[i], tdir);
+        }
+    }
+
+    let mut output = Vec::with_capacity(count);
+    for i in 0..count {
+        let n = normals[i];
+        let t = tangents[i];
+        // Gram-Schmidt orthogonalize against the normal.
+        let projected = math::sub(t, math::scale(n, math::dot(n, t)));
+        let mut tangent = math::normalize(projected);
+        if tangent == [0.0, 0.0, 0.0] {
+            tangent = math::perpendicular(n);
+        }
+        let w = if math::dot(math::cross(n, tangent), bitangents[i]) < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        output.push([tangent[0], tangent[1], tangent[2], w]);
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_generates_tangents_with_handedness() {
+        let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let normals = [[0.0, 0.0, 1.0]; 3];
+        let tex_coords = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+        let tangents = generate(&positions, &normals, &tex_coords, vec![[0, 1, 2]]);
+        assert_eq!(vec![[1.0, 0.0, 0.0, 1.0]; 3], tangents);
+
+        // Mirroring the texture vertically flips the handedness.
+        let mirrored = [[0.0, 1.0], [1.0, 1.0], [0.0, 0.0]];
+        let tangents = generate(&positions, &normals, &mirrored, vec![[0, 1, 2]]);
+        assert_eq!(vec![[1.0, 0.0, 0.0, -1.0]; 3], tangents);
+    }
+
+    #[test]
+    fn it_handles_degenerate_tex_coords() {
+        let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let normals = [[0.0, 0.0, 1.0]; 3];
+        let tex_coords = [[0.0, 0.0]; 3];
+        let tangents = generate(&positions, &normals, &tex_coords, vec![[0, 1, 2]]);
+        for tangent in tangents {
+            assert_eq!(0.0, tangent[2]);
+            assert_eq!(1.0, tangent[0] * tangent[0] + tangent[1] * tangent[1]);
+        }
+    }
+
+    #[test]
+    fn it_skips_out_of_range_vertices() {
+        let positions = [
+            [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0],
+        ];
+        let normals = [[0.0, 0.0, 1.0]; 4];
+        let tex_coords = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+        let triangles = vec![[0, 1, 2], [1, 3, 2], [0, 1, 7]];
+        let tangents = generate(&positions, &normals, &tex_coords, triangles);
+        assert_eq!(vec![[1.0, 0.0, 0.0, 1.0]; 3], tangents);
+    }
+}
@@ -0,0 +1,238 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::HashMap;
+use v1::Gltf;
+use v1::accessor::ComponentType;
+use v1::buffer::Buffers;
+use v1::builder::Builder;
+use v1::mesh::{PackedAttribute, Primitive, Semantic, Vertex, VertexOptions};
+
+/// Deduplicates vertices and generates an index list.
+///
+/// Two vertices are merged when every component of every attribute differs by
+/// at most `epsilon`, and both have the same set of attributes. Joint indices
+/// must match exactly. The first vertex of each merged group is kept.
+///
+/// Returns the unique vertices and, for each input vertex, the index of its
+/// unique vertex.
+pub fn weld(vertices: &[Vertex], epsilon: f32) -> (Vec<Vertex>, Vec<u32>) {
+    let (sources, indices) = weld_with(vertices, epsilon, |_, _| true);
+    (sources.iter().map(|&i| vertices[i]).collect(), indices)
+}
+
+/// Welds the vertices of a primitive, returning a copy of it whose attributes
+/// hold only the unique vertices and whose `indices` reference them.
+///
+/// The vertices of primitives without indices are those of `0..count`, and
+/// the indices of indexed primitives are remapped, so this works for both.
+/// Vertices are compared as `weld` does, and attributes that `Vertex` does
+/// not hold, such as a second texture co-ordinate set, must match exactly.
+/// The new accessors are named after `id` and appended to `buffer`.
+///
+/// Returns `None` if the primitive has no `POSITION` attribute, or if the
+/// data of an attribute or the indices cannot be read from `buffers`.
+pub fn weld_primitive(
+    gltf: &mut Gltf,
+    buffers: &mut Buffers,
+    buffer: &str,
+    primitive: &Primitive,
+    id: &str,
+    epsilon: f32,
+) -> Option<Primitive> {
+    let options = VertexOptions::default();
+    let vertices: Vec<_> = primitive.read_vertices(gltf, buffers, &options)?.collect();
+    let old_indices = primitive.read_indices(gltf, buffers)?;
+    let attributes = PackedAttribute::read_all(gltf, buffers, primitive, vertices.len())?;
+    let exact: Vec<_> = attributes.iter()
+        .filter(|attribute| {
+            let semantic = Semantic::from_attribute(&attribute.name);
+            !in_vertex(&semantic, attribute.component_type)
+        })
+        .collect();
+    let (sources, remap) = weld_with(&vertices, epsilon, |a, b| {
+        exact.iter().all(|attribute| attribute.element(a) == attribute.element(b))
+    });
+    let indices: Vec<u32> = old_indices.iter()
+        .map(|&i| remap.get(i as usize).cloned())
+        .collect::<Option<_>>()?;
+
+    let mut builder = Builder::new(gltf, buffers, buffer);
+    let mut welded = Primitive {
+        attributes: HashMap::new(),
+        ..primitive.clone()
+    };
+    for attribute in &attributes {
+        let new_id = attribute.write(&mut builder, id, sources.iter().cloned());
+        welded.attributes.insert(attribute.name.clone(), new_id);
+    }
+    welded.indices = Some(builder.indices(&format!("{}_indices", id), &indices));
+    Some(welded)
+}
+
+/// Returns whether an attribute is compared through `Vertex` when welding.
+fn in_vertex(semantic: &Semantic, component_type: ComponentType) -> bool {
+    match *semantic {
+        Semantic::Position | Semantic::Normal => true,
+        Semantic::TexCoord(set) | Semantic::Joint(set) | Semantic::Weight(set) => set == 0,
+        Semantic::Color(0) => {
+            use v1::accessor::ComponentType::{F32, U16, U8};
+            matches!(component_type, F32 | U8 | U16)
+        },
+        Semantic::Color(_) => false,
+        Semantic::Extra(_) => false,
+    }
+}
+
+/// Welds vertices that are within `epsilon` of each other and for which
+/// `compatible` holds, given their indices in `vertices`.
+///
+/// Returns the index in `vertices` of the first vertex of each merged group,
+/// and for each vertex the index of its group.
+fn weld_with<F>(vertices: &[Vertex], epsilon: f32, compatible: F) -> (Vec<usize>, Vec<u32>)
+    where F: Fn(usize, usize) -> bool
+{
+    let mut sources: Vec<usize> = Vec::new();
+    let mut indices = Vec::with_capacity(vertices.len());
+    let mut cells: HashMap<[i64; 3], Vec<u32>> = HashMap::new();
+    for (i, vertex) in vertices.iter().enumerate() {
+        let cell = cell(vertex.position, epsilon);
+        let found = neighbours(cell, epsilon)
+            .iter()
+            .filter_map(|neighbour| cells.get(neighbour))
+            .flat_map(|candidates| candidates.iter())
+            .find(|&&group| {
+                let source = sources[group as usize];
+                approx_eq(&vertices[source], vertex, epsilon)
+                    && compatible(source, i)
+            })
+            .cloned();
+        let index = match found {
+            Some(index) => index,
+            None => {
+                let index = sources.len() as u32;
+                sources.push(i);
+                cells.entry(cell).or_default().push(index);
+                index
+            },
+        };
+        indices.push(index);
+    }
+    (sources, indices)
+}
+
+/// Returns the spatial hash cell containing a position.
+fn cell(position: [f32; 3], epsilon: f32) -> [i64; 3] {
+    let mut cell = [0; 3];
+    for (c, &x) in cell.iter_mut().zip(position.iter()) {
+        *c = if epsilon > 0.0 {
+            (x / epsilon).floor() as i64
+        } else {
+            // Exact matching: distinguish positions by value, treating -0.0
+            // and 0.0 as equal.
+            (x + 0.0).to_bits() as i64
+        };
+    }
+    cell
+}
+
+/// Returns the cells that may contain positions within `epsilon` of a
+/// position in `cell`.
+fn neighbours(cell: [i64; 3], epsilon: f32) -> Vec<[i64; 3]> {
+    if epsilon > 0.0 {
+        let mut cells = Vec::with_capacity(27);
+        for x in -1..2 {
+            for y in -1..2 {
+                for z in -1..2 {
+                    cells.push([cell[0] + x, cell[1] + y, cell[2] + z]);
+                }
+            }
+        }
+        cells
+    } else {
+        vec![cell]
+    }
+}
+
+fn approx_eq(a: &Vertex, b: &Vertex, epsilon: f32) -> bool {
+    near(&a.position, &b.position, epsilon)
+        && near_option(&a.normal, &b.normal, epsilon)
+        && near_option(&a.tex_coord, &b.tex_coord, epsilon)
+        && near_option(&a.color, &b.color, epsilon)
+        && near_option(&a.weights, &b.weights, epsilon)
+        && a.joints == b.joints
+}
+
+fn near(a: &[f32], b: &[f32], epsilon: f32) -> bool {
+    a.iter().zip(b).all(|(x, y)| (x - y).abs() <= epsilon)
+}
+
+fn near_option<T: AsRef<[f32]>>(a: &Option<T>, b: &Option<T>, epsilon: f32) -> bool {
+    match (a.as_ref(), b.as_ref()) {
+        (Some(a), Some(b)) => near(a.as_ref(), b.as_ref(), epsilon),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn vertex(position: [f32; 3]) -> Vertex {
+        Vertex { position, ..Default::default() }
+    }
+
+    #[test]
+    fn it_welds_nearby_vertices() {
+        let vertices = [
+            vertex([0.0, 0.0, 0.0]),
+            vertex([1.0, 0.0, 0.0]),
+            vertex([-0.0005, 0.0, 0.0004]),
+            vertex([0.0, 1.0, 0.0]),
+            vertex([1.0, 0.0, 0.0]),
+            Vertex { normal: Some([0.0, 0.0, 1.0]), ..vertex([0.0, 1.0, 0.0]) },
+        ];
+        let (unique, indices) = weld(&vertices, 0.001);
+        assert_eq!(vec![0, 1, 0, 2, 1, 3], indices);
+        assert_eq!(4, unique.len());
+
+        let (unique, indices) = weld(&vertices, 0.0);
+        assert_eq!(vec![0, 1, 2, 3, 1, 4], indices);
+        assert_eq!(5, unique.len());
+    }
+
+    #[test]
+    fn it_welds_primitives() {
+        let mut gltf = Gltf::default();
+        let mut buffers = Buffers::new();
+        let primitive = {
+            let mut builder = Builder::new(&mut gltf, &mut buffers, "buffer");
+            let positions = [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0],
+                             [0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]];
+            let ids = [1.0f32, 1.0, 1.0, 2.0, 1.0, 1.0];
+            let mut primitive = Primitive::default();
+            primitive.attributes.insert(
+                "POSITION".to_string(), builder.accessor("positions", &positions, None));
+            primitive.attributes.insert(
+                "_ID".to_string(), builder.accessor("ids", &ids, None));
+            primitive
+        };
+        let welded = weld_primitive(&mut gltf, &mut buffers, "buffer", &primitive,
+                                    "welded", 0.0).unwrap();
+        let indices = welded.read_indices(&gltf, &buffers).unwrap();
+        assert_eq!(vec![0, 1, 2, 3, 1, 4], indices);
+        assert_eq!(Some(5), welded.vertex_count(&gltf));
+        let ids: Vec<f32> = gltf.accessors[&welded.attributes["_ID"]]
+            .view(&gltf, &buffers)
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(vec![1.0, 1.0, 1.0, 2.0, 1.0], ids);
+    }
+}
@@ -6,29 +6,49 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use base64;
 use serde_json;
-use std::fs::File;
+use std::error::Error as StdError;
+use std::fmt;
+use std::fs;
 use std::io;
-use std::io::Read;
 use std::path::Path;
 use std::collections::HashMap;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
 
 pub mod accessor;
 pub mod animation;
 pub mod asset;
+pub mod binary;
 pub mod buffer;
+pub mod builder;
 pub mod camera;
+pub mod export;
+pub mod extensions;
+pub mod extras;
+pub mod gl;
 pub mod image;
+pub mod import;
 pub mod material;
+mod math;
 pub mod mesh;
 pub mod node;
+pub mod optimize;
 pub mod program;
+pub mod query;
 pub mod sampler;
 pub mod scene;
 pub mod shader;
 pub mod skin;
+pub mod stats;
+pub mod stream;
 pub mod technique;
 pub mod texture;
+pub mod validation;
+pub mod vk;
+
+pub use self::import::{import, import_cached, import_slice, import_with};
 
 #[derive(Debug)]
 pub enum Error {
@@ -36,6 +56,89 @@ pub enum Error {
     Io(io::Error),
     /// Failure when parsing a .gltf metadata file
     Parse(serde_json::error::Error),
+    /// Failure when decoding a base64 data URI
+    Base64(base64::DecodeError),
+    /// A URI that is neither a data URI nor a relative path
+    UnsupportedUri(String),
+    /// The data of the buffer with the given ID is shorter than its byteLength
+    BufferLength(String),
+    /// The asset failed validation
+    Validation(Vec<validation::Error>),
+    /// A malformed binary glTF container
+    Binary(binary::Error),
+    /// An embedded image or shader refers to a bufferView outside its buffer
+    BufferView(String),
+    /// The asset declares a glTF version other than 1.x
+    UnsupportedVersion(String),
+    /// Failure when loading the data of a buffer or image from its URI
+    Load {
+        /// The object whose data failed to load, e.g. `/images/wood`.
+        pointer: validation::Pointer,
+        /// The URI of the data.
+        uri: String,
+        /// The underlying error.
+        cause: Box<Error>,
+    },
+    /// The data of a buffer or image does not match the SHA-256 checksum it
+    /// declares
+    Checksum {
+        /// The object whose data is corrupt, e.g. `/buffers/geometry`.
+        pointer: validation::Pointer,
+        /// The URI of the data.
+        uri: String,
+        /// The declared checksum, as a hexadecimal string.
+        expected: String,
+        /// The checksum of the loaded data, as a lowercase hexadecimal string.
+        found: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "I/O error: {}", err),
+            Error::Parse(ref err) => write!(f, "invalid glTF JSON: {}", err),
+            Error::Base64(ref err) => write!(f, "invalid base64 data URI: {}", err),
+            Error::UnsupportedUri(ref uri) => write!(f, "unsupported URI: {}", uri),
+            Error::BufferLength(ref id) => {
+                write!(f, "buffer {} is shorter than its byteLength", id)
+            },
+            Error::Validation(ref errors) => {
+                write!(f, "invalid glTF asset with {} errors", errors.len())?;
+                if let Some(first) = errors.first() {
+                    write!(f, ", the first being {}", first)?;
+                }
+                Ok(())
+            },
+            Error::Binary(ref err) => write!(f, "invalid binary glTF: {}", err),
+            Error::BufferView(ref id) => {
+                write!(f, "bufferView {} lies outside its buffer", id)
+            },
+            Error::UnsupportedVersion(ref version) => {
+                write!(f, "unsupported glTF version {}", version)
+            },
+            Error::Load { ref pointer, ref uri, ref cause } => {
+                write!(f, "failed to load {} for {}: {}", uri, pointer, cause)
+            },
+            Error::Checksum { ref pointer, ref uri, ref expected, ref found } => {
+                write!(f, "checksum mismatch for {} of {}: expected SHA-256 {}, found {}",
+                       uri, pointer, expected, found)
+            },
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Parse(ref err) => Some(err),
+            Error::Base64(ref err) => Some(err),
+            Error::Binary(ref err) => Some(err),
+            Error::Load { ref cause, .. } => Some(&**cause),
+            _ => None,
+        }
+    }
 }
 
 impl From<io::Error> for Error {
@@ -50,6 +153,12 @@ impl From<serde_json::error::Error> for Error {
     }
 }
 
+impl From<binary::Error> for Error {
+    fn from(err: binary::Error) -> Error {
+        Error::Binary(err)
+    }
+}
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Gltf {
     /// A dictionary object of accessor objects.
@@ -65,7 +174,7 @@ pub struct Gltf {
     /// The name of each animation is an ID in the global glTF namespace that is
     /// used to reference the animation.
     #[serde(default)]
-    pub animation: HashMap<String, animation::Animation>,
+    pub animations: HashMap<String, animation::Animation>,
 
     /// Metadata about the glTF asset.
     #[serde(default)]
@@ -95,6 +204,11 @@ pub struct Gltf {
     #[serde(default)]
     pub cameras: HashMap<String, camera::Camera>,
 
+    /// Names of extensions used somewhere in this asset.
+    #[serde(default)]
+    #[serde(rename = "extensionsUsed")]
+    pub extensions_used: Vec<String>,
+
     /// A dictionary object of image objects.
     ///
     /// The name of each image is an ID in the global glTF namespace that is
@@ -179,16 +293,138 @@ pub struct Gltf {
     #[serde(default)]
     pub textures: HashMap<String, texture::Texture>, 
 
-    // TODO: extension
-    // TODO: extras
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
 }
 
 impl Gltf {
-    pub fn open(path: &Path) -> Result<Self, Error> {
-        let mut file = File::open(path)?;
-        let mut json = String::new();
-        file.read_to_string(&mut json)?;
+    /// Returns the scene to render.
+    ///
+    /// This is the scene named by the `scene` property, or the only scene when
+    /// the property is absent and the asset contains exactly one scene.
+    pub fn default_scene(&self) -> Option<&scene::Scene> {
+        match self.scene {
+            Some(ref id) => self.scenes.get(id),
+            None if self.scenes.len() == 1 => self.scenes.values().next(),
+            None => None,
+        }
+    }
 
-        serde_json::from_str(&json).map_err(|cause| Error::Parse(cause))
+    /// Returns the ID of the animation with the given user-defined name, and
+    /// the animation itself.
+    ///
+    /// Names need not be unique; when several animations share a name, the
+    /// one with the smallest ID is returned.
+    pub fn animation_by_name(&self, name: &str) -> Option<(&str, &animation::Animation)> {
+        self.animations.iter()
+            .filter(|&(_, animation)| animation.name.as_ref().is_some_and(|n| n == name))
+            .map(|(id, animation)| (id.as_str(), animation))
+            .min_by_key(|&(id, _)| id)
+    }
+
+    /// Calls `f` with a pointer to every object that has extras, such as
+    /// `/nodes/tree` or `/meshes/tree/primitives/0`, and its extras.
+    ///
+    /// Every kind of object is visited, including nested objects such as
+    /// animation channels and technique parameters, and the root itself with
+    /// the empty pointer. Objects are visited in a fixed order, with the
+    /// members of each dictionary object sorted by ID.
+    pub fn visit_extras<F>(&self, mut f: F)
+        where F: FnMut(&validation::Pointer, &Extras)
+    {
+        extras::visit(self, &mut f);
+    }
+
+    /// Returns counts and byte totals of the asset, see `stats::Stats`.
+    pub fn stats(&self) -> stats::Stats {
+        stats::Stats::new(self)
+    }
+
+    /// Checks the asset against the rules of the glTF specification.
+    ///
+    /// Returns every problem found; an empty list means the asset is valid.
+    pub fn validate(&self) -> Vec<validation::Error> {
+        self.validate_with(validation::Validation::Complete)
+    }
+
+    /// Checks the asset at the given level of thoroughness.
+    pub fn validate_with(&self, level: validation::Validation) -> Vec<validation::Error> {
+        use self::validation::{Ids, Pointer, References, Validate, Validation};
+        let root = Pointer::root();
+        let mut errors = Vec::new();
+        if level == Validation::Skip {
+            return errors;
+        }
+
+        self.scene.validate_ids(root.field("scene"), &self.scenes, &mut errors);
+        self.accessors.validate_references(self, &root.field("accessors"), &mut errors);
+        self.animations.validate_references(self, &root.field("animations"), &mut errors);
+        self.buffer_views
+            .validate_references(self, &root.field("bufferViews"), &mut errors);
+        self.materials.validate_references(self, &root.field("materials"), &mut errors);
+        self.meshes.validate_references(self, &root.field("meshes"), &mut errors);
+        self.nodes.validate_references(self, &root.field("nodes"), &mut errors);
+        self.programs.validate_references(self, &root.field("programs"), &mut errors);
+        self.scenes.validate_references(self, &root.field("scenes"), &mut errors);
+        self.skins.validate_references(self, &root.field("skins"), &mut errors);
+        self.techniques.validate_references(self, &root.field("techniques"), &mut errors);
+        self.textures.validate_references(self, &root.field("textures"), &mut errors);
+        if level == Validation::Minimal {
+            return errors;
+        }
+
+        for (id, accessor) in &self.accessors {
+            let pointer = root.field("accessors").field(id);
+            accessor.validate(self, &pointer, &mut errors);
+        }
+        for (id, animation) in &self.animations {
+            let pointer = root.field("animations").field(id);
+            animation.validate(self, &pointer, &mut errors);
+        }
+        for (id, camera) in &self.cameras {
+            let pointer = root.field("cameras").field(id);
+            camera.validate(self, &pointer, &mut errors);
+        }
+        for (id, mesh) in &self.meshes {
+            let pointer = root.field("meshes").field(id);
+            mesh.validate(self, &pointer, &mut errors);
+        }
+        for (id, node) in &self.nodes {
+            let pointer = root.field("nodes").field(id);
+            node.validate(self, &pointer, &mut errors);
+        }
+        node::validate_hierarchy(self, &mut errors);
+        for (id, skin) in &self.skins {
+            let pointer = root.field("skins").field(id);
+            skin.validate(self, &pointer, &mut errors);
+        }
+        validation::report_unused(self, &mut errors);
+        errors
+    }
+
+    /// Loads the asset at the given path, which is either a .gltf file or a
+    /// binary glTF container.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Gltf::from_slice(&fs::read(path)?)
+    }
+
+    /// Parses either JSON or a binary glTF container.
+    ///
+    /// The body of a binary container is ignored; use `binary::Glb` to access
+    /// it.
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        let json = if binary::is_binary(data) {
+            binary::Glb::from_slice(data)?.content
+        } else {
+            data
+        };
+        serde_json::from_slice(json).map_err(|cause| Error::Parse(cause))
     }
 }
@@ -6,6 +6,17 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::collections::HashMap;
+
+use v1::Gltf;
+use v1::animation::Channel;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+use v1::math;
+use v1::mesh::Semantic;
+use v1::scene::{Order, Traverse};
+use v1::validation::{report, report_value, Code, Error, Pointer, Validate};
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Node {
     /// The ID of the camera referenced by this node.
@@ -30,8 +41,11 @@ pub struct Node {
     pub joint_name: Option<String>,
 
     /// A floating-point 4x4 transformation matrix stored in column-major order.
-    #[serde(default = "node_matrix_default")]
-    pub matrix: [f32; 16],
+    ///
+    /// When present, the node must not also define a translation, rotation or
+    /// scale.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<[f32; 16]>,
 
     /// The IDs of the meshes in this node.
     ///
@@ -41,32 +55,464 @@ pub struct Node {
 
     /// The node's unit quaternion rotation in the order (x, y, z, w),
     /// where w is the scalar.
-    #[serde(default = "node_rotation_default")]
-    pub rotation: [f32; 4],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<[f32; 4]>,
 
     /// The node's non-uniform scale.
-    #[serde(default = "node_scale_default")]
-    pub scale: [f32; 3],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<[f32; 3]>,
 
     /// The node's translation.
-    #[serde(default = "node_translation_default")]
-    pub translation: [f32; 3],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translation: Option<[f32; 3]>,
 
     pub name: Option<String>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+/// The local transform of a node.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transform {
+    /// A 4x4 transformation matrix stored in column-major order.
+    Matrix([f32; 16]),
+
+    /// Translation, rotation and scale, applied as `T * R * S`.
+    Decomposed {
+        /// The translation.
+        translation: [f32; 3],
+
+        /// The unit quaternion rotation in the order (x, y, z, w).
+        rotation: [f32; 4],
+
+        /// The non-uniform scale.
+        scale: [f32; 3],
+    },
 }
 
-fn node_matrix_default() -> [f32; 16] {
-    [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]
+impl Transform {
+    /// Returns the transform as a column-major 4x4 matrix.
+    pub fn matrix(&self) -> [f32; 16] {
+        match *self {
+            Transform::Matrix(matrix) => matrix,
+            Transform::Decomposed { translation, rotation, scale } => {
+                math::from_trs(translation, rotation, scale)
+            },
+        }
+    }
 }
 
-fn node_rotation_default() -> [f32; 4] {
-    [0.0, 0.0, 0.0, 1.0]
+impl Node {
+    /// Returns the local transform of this node.
+    ///
+    /// A node that defines a matrix uses it; otherwise its translation,
+    /// rotation and scale are used, with any missing property taking its
+    /// default value.
+    pub fn transform(&self) -> Transform {
+        match self.matrix {
+            Some(matrix) => Transform::Matrix(matrix),
+            None => Transform::Decomposed {
+                translation: self.translation.unwrap_or([0.0, 0.0, 0.0]),
+                rotation: self.rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]),
+                scale: self.scale.unwrap_or([1.0, 1.0, 1.0]),
+            },
+        }
+    }
+
+    /// Returns the local transform of this node as a column-major 4x4 matrix.
+    pub fn local_matrix(&self) -> [f32; 16] {
+        self.transform().matrix()
+    }
+
+    /// Returns every animation channel targeting the node with the given ID,
+    /// across all animations, as pairs of animation ID and channel.
+    ///
+    /// The pairs are sorted by animation ID and keep the order of the
+    /// channels within each animation.
+    pub fn channels<'a>(id: &str, gltf: &'a Gltf) -> Vec<(&'a str, &'a Channel)> {
+        let mut channels: Vec<_> = gltf.animations.iter()
+            .flat_map(|(animation, value)| {
+                value.channels.iter().map(move |channel| (animation.as_str(), channel))
+            })
+            .filter(|&(_, channel)| channel.target.id == id)
+            .collect();
+        channels.sort_by_key(|&(animation, _)| animation);
+        channels
+    }
+
+    /// Visits the descendants of this node in depth-first pre-order.
+    ///
+    /// `transform` is the world transform of this node, which is accumulated
+    /// into the transform of each descendant. The node's children are visited
+    /// at depth zero.
+    pub fn descendants<'a>(
+        &'a self,
+        gltf: &'a Gltf,
+        transform: &[f32; 16],
+    ) -> Traverse<'a> {
+        Traverse::new(gltf, &self.children, transform, Order::DepthFirst)
+    }
 }
 
-fn node_scale_default() -> [f32; 3] {
-    [1.0, 1.0, 1.0]
+/// The parent of every node in an asset.
+///
+/// glTF only records the children of each node; this table answers the
+/// reverse question for any node ID.
+#[derive(Clone, Debug, Default)]
+pub struct Hierarchy {
+    parents: HashMap<String, String>,
 }
 
-fn node_translation_default() -> [f32; 3] {
-    [0.0, 0.0, 0.0]
+impl Hierarchy {
+    /// Builds the parent table of the nodes in `gltf`.
+    ///
+    /// If a node is listed as the child of more than one node, which the
+    /// specification forbids, one of them is chosen arbitrarily.
+    pub fn new(gltf: &Gltf) -> Self {
+        let mut parents = HashMap::new();
+        for (id, node) in &gltf.nodes {
+            for child in &node.children {
+                parents.insert(child.clone(), id.clone());
+            }
+        }
+        Hierarchy { parents }
+    }
+
+    /// Returns the ID of the parent of the given node.
+    pub fn parent(&self, id: &str) -> Option<&str> {
+        self.parents.get(id).map(String::as_str)
+    }
+
+    /// Returns `true` if the given node has no parent.
+    pub fn is_root(&self, id: &str) -> bool {
+        !self.parents.contains_key(id)
+    }
+
+    /// Visits the ancestors of the given node, from its parent upward.
+    ///
+    /// The iterator stops early if the hierarchy contains a cycle.
+    pub fn ancestors<'a>(&'a self, id: &'a str) -> Ancestors<'a> {
+        Ancestors {
+            hierarchy: self,
+            current: id,
+            remaining: self.parents.len(),
+        }
+    }
+}
+
+/// An iterator over the ancestors of a node.
+#[derive(Clone, Debug)]
+pub struct Ancestors<'a> {
+    hierarchy: &'a Hierarchy,
+    current: &'a str,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let parent = self.hierarchy.parent(self.current)?;
+        self.current = parent;
+        Some(parent)
+    }
+}
+
+impl Validate for Node {
+    fn validate(&self, gltf: &Gltf, pointer: &Pointer, errors: &mut Vec<Error>) {
+        let decomposed = self.translation.is_some()
+            || self.rotation.is_some()
+            || self.scale.is_some();
+        if self.matrix.is_some() && decomposed {
+            let description = "matrix must not be defined alongside translation, \
+                               rotation or scale";
+            report(errors, Code::Conflict, pointer.field("matrix"),
+                   description.to_string());
+        }
+        if self.skin.is_some() {
+            self.validate_skinned_meshes(gltf, pointer, errors);
+        }
+    }
+}
+
+impl Node {
+    /// Checks that every primitive of a skinned node's meshes has the joint
+    /// and weight attributes that skinning requires.
+    fn validate_skinned_meshes(
+        &self,
+        gltf: &Gltf,
+        pointer: &Pointer,
+        errors: &mut Vec<Error>,
+    ) {
+        let required = [(Semantic::Joint(0), "JOINT"), (Semantic::Weight(0), "WEIGHT")];
+        for (i, id) in self.meshes.iter().enumerate() {
+            let mesh = match gltf.meshes.get(id) {
+                Some(mesh) => mesh,
+                None => continue,
+            };
+            for (j, primitive) in mesh.primitives.iter().enumerate() {
+                for &(ref semantic, name) in &required {
+                    if primitive.attribute(semantic).is_none() {
+                        report_value(errors, Code::MissingProperty,
+                                     pointer.field("meshes").index(i), id.as_str(),
+                                     format!("primitive {} of a skinned mesh has no {} \
+                                              attribute", j, name));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Checks that the nodes of `gltf` form a forest: every node has at most one
+/// parent and no node is its own ancestor.
+pub(crate) fn validate_hierarchy(gltf: &Gltf, errors: &mut Vec<Error>) {
+    let children_pointer = |id: &str, i: usize| {
+        Pointer::root().field("nodes").field(id).field("children").index(i)
+    };
+    let mut ids: Vec<&String> = gltf.nodes.keys().collect();
+    ids.sort();
+
+    let mut parents = HashMap::new();
+    for &id in &ids {
+        for (i, child) in gltf.nodes[id].children.iter().enumerate() {
+            if let Some(first) = parents.insert(child.as_str(), id.as_str()) {
+                report_value(errors, Code::MultipleParents, children_pointer(id, i),
+                             child.as_str(),
+                             format!("node is already a child of {:?}", first));
+            }
+        }
+    }
+
+    // Depth-first search; a child that is still on the stack closes a cycle.
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Active,
+        Done,
+    }
+    let mut states = HashMap::new();
+    for &root in &ids {
+        if states.contains_key(root.as_str()) {
+            continue;
+        }
+        states.insert(root.as_str(), State::Active);
+        let mut stack = vec![(root.as_str(), 0)];
+        while let Some(&mut (id, ref mut next)) = stack.last_mut() {
+            let children = &gltf.nodes[id].children;
+            if *next == children.len() {
+                states.insert(id, State::Done);
+                stack.pop();
+                continue;
+            }
+            let i = *next;
+            *next += 1;
+            let child = children[i].as_str();
+            if !gltf.nodes.contains_key(child) {
+                continue;
+            }
+            match states.get(child) {
+                Some(&State::Active) => {
+                    report_value(errors, Code::Cycle, children_pointer(id, i), child,
+                                 "node is its own ancestor".to_string());
+                },
+                Some(&State::Done) => {},
+                None => {
+                    states.insert(child, State::Active);
+                    stack.push((child, 0));
+                },
+            }
+        }
+    }
+}
+
+impl_references!(Node {
+    ids {
+        camera: "camera" => cameras,
+        children: "children" => nodes,
+        meshes: "meshes" => meshes,
+        skeletons: "skeletons" => nodes,
+        skin: "skin" => skins,
+    }
+    nested {}
+});
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+    use v1::validation::References;
+
+    #[test]
+    fn it_finds_channels_targeting_a_node() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "animations": {
+                "walk": {
+                    "name": "Walk",
+                    "channels": [
+                        { "sampler": "a", "target": { "id": "hip", "path": "rotation" } },
+                        { "sampler": "b", "target": { "id": "knee", "path": "rotation" } },
+                        { "sampler": "c", "target": { "id": "hip", "path": "translation" } }
+                    ]
+                },
+                "idle": {
+                    "name": "Idle",
+                    "channels": [
+                        { "sampler": "a", "target": { "id": "hip", "path": "scale" } }
+                    ]
+                }
+            },
+            "nodes": { "hip": {}, "knee": {} }
+        }"#).unwrap();
+        let channels: Vec<_> = Node::channels("hip", &gltf).into_iter()
+            .map(|(animation, channel)| (animation, channel.sampler.as_str()))
+            .collect();
+        assert_eq!(vec![("idle", "a"), ("walk", "a"), ("walk", "c")], channels);
+        assert_eq!(Some("walk"), gltf.animation_by_name("Walk").map(|(id, _)| id));
+        assert!(gltf.animation_by_name("walk").is_none());
+    }
+
+    #[test]
+    fn it_selects_the_authored_transform() {
+        let matrix: Node = serde_json::from_str(r#"{
+            "matrix": [1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 4, 5, 6, 1]
+        }"#).unwrap();
+        match matrix.transform() {
+            Transform::Matrix(m) => assert_eq!([4.0, 5.0, 6.0], [m[12], m[13], m[14]]),
+            transform => panic!("expected a matrix, found {:?}", transform),
+        }
+
+        let trs: Node = serde_json::from_str(r#"{
+            "translation": [1, 2, 3],
+            "scale": [2, 2, 2]
+        }"#).unwrap();
+        let m = trs.local_matrix();
+        assert_eq!(2.0, m[0]);
+        assert_eq!([1.0, 2.0, 3.0], [m[12], m[13], m[14]]);
+    }
+
+    #[test]
+    fn it_rejects_matrix_with_trs() {
+        let node: Node = serde_json::from_str(r#"{
+            "matrix": [1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1],
+            "scale": [2, 2, 2]
+        }"#).unwrap();
+        let mut errors = Vec::new();
+        let pointer = Pointer::root().field("nodes").field("a");
+        node.validate(&Gltf::default(), &pointer, &mut errors);
+        assert_eq!(1, errors.len());
+        assert_eq!("/nodes/a/matrix", errors[0].pointer.as_str());
+        assert_eq!(Code::Conflict, errors[0].code);
+    }
+
+    #[test]
+    fn it_finds_parents_and_ancestors() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "nodes": {
+                "a": { "children": ["b"] },
+                "b": { "children": ["c"] },
+                "c": {}
+            }
+        }"#).unwrap();
+        let hierarchy = Hierarchy::new(&gltf);
+        assert!(hierarchy.is_root("a"));
+        assert!(!hierarchy.is_root("c"));
+        assert_eq!(Some("b"), hierarchy.parent("c"));
+        assert_eq!(vec!["b", "a"], hierarchy.ancestors("c").collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn it_detects_cycles_and_shared_children() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "nodes": {
+                "a": { "children": ["b"] },
+                "b": { "children": ["a", "c"] },
+                "c": {},
+                "d": { "children": ["c"] }
+            }
+        }"#).unwrap();
+        let mut errors = Vec::new();
+        validate_hierarchy(&gltf, &mut errors);
+        let mut pointers: Vec<String> = errors
+            .into_iter()
+            .map(|error| error.pointer.to_string())
+            .collect();
+        pointers.sort();
+        assert_eq!(vec![
+            "/nodes/b/children/0",
+            "/nodes/d/children/0",
+        ], pointers);
+    }
+
+    #[test]
+    fn it_requires_skinning_attributes() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "meshes": {
+                "skinned": {
+                    "primitives": [
+                        { "attributes": { "JOINT": "j", "WEIGHT": "w" }, "material": "m" },
+                        { "attributes": { "JOINT": "j" }, "material": "m" }
+                    ]
+                }
+            },
+            "nodes": {
+                "a": { "meshes": ["skinned"], "skin": "skin" },
+                "b": { "meshes": ["skinned"] }
+            }
+        }"#).unwrap();
+        let mut errors = Vec::new();
+        let pointer = Pointer::root().field("nodes").field("a");
+        gltf.nodes["a"].validate(&gltf, &pointer, &mut errors);
+        assert_eq!(1, errors.len());
+        assert_eq!("/nodes/a/meshes/0", errors[0].pointer.as_str());
+        assert!(errors[0].description.contains("primitive 1"));
+        errors.clear();
+        gltf.nodes["b"].validate(&gltf, &pointer, &mut errors);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn it_checks_every_reference() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "cameras": {
+                "camera": { "type": "perspective" }
+            },
+            "nodes": {
+                "valid": { "camera": "camera", "children": ["leaf"] },
+                "leaf": {},
+                "invalid": {
+                    "children": ["leaf", "no_child"],
+                    "meshes": ["no_mesh"],
+                    "skeletons": ["no_skeleton"],
+                    "skin": "no_skin"
+                },
+                "no_camera": { "camera": "no_camera" }
+            }
+        }"#).unwrap();
+        let pointers = |id: &str| {
+            let mut errors = Vec::new();
+            let pointer = Pointer::root().field("nodes").field(id);
+            gltf.nodes[id].validate_references(&gltf, &pointer, &mut errors);
+            errors.into_iter().map(|error| error.pointer.to_string()).collect::<Vec<_>>()
+        };
+        assert!(pointers("valid").is_empty());
+        assert_eq!(vec!["/nodes/no_camera/camera"], pointers("no_camera"));
+        assert_eq!(vec![
+            "/nodes/invalid/children/1",
+            "/nodes/invalid/meshes/0",
+            "/nodes/invalid/skeletons/0",
+            "/nodes/invalid/skin",
+        ], pointers("invalid"));
+    }
 }
@@ -0,0 +1,215 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use v1::Gltf;
+use v1::buffer::{Buffers, Target};
+use v1::builder::Builder;
+use v1::mesh::Semantic;
+
+/// The placement of an image within an atlas.
+///
+/// Texture co-ordinates `uv` of the packed image become `offset + uv * scale`
+/// in the atlas.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Region {
+    /// The position of the image within the atlas.
+    pub offset: [f32; 2],
+
+    /// The size of the image relative to the atlas.
+    pub scale: [f32; 2],
+}
+
+impl Region {
+    fn map(&self, uv: [f32; 2]) -> [f32; 2] {
+        [self.offset[0] + uv[0] * self.scale[0], self.offset[1] + uv[1] * self.scale[1]]
+    }
+}
+
+/// An attribute of a primitive, as the mesh ID, the index of the primitive,
+/// and the attribute name.
+type Attribute<'a> = (&'a str, usize, &'a str);
+
+/// A packing of several images into one atlas image.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Atlas {
+    /// The ID of the atlas image, which must exist in the asset.
+    pub image: String,
+
+    /// The region of each packed image, keyed by image ID.
+    pub regions: HashMap<String, Region>,
+}
+
+/// Rewrites materials sampling packed images to sample the atlas instead.
+///
+/// Every texture co-ordinate set of the primitives using such a material is
+/// remapped into a new accessor appended to `buffer`, and the material is
+/// pointed at copies of its textures whose source is the atlas image. Since
+/// a set of texture co-ordinates can only be remapped into one region, a
+/// material is left unchanged when its textures are not all packed into the
+/// same region. The IDs of those materials are returned.
+///
+/// Atlas regions cannot repeat, so packed textures should not rely on
+/// wrapping.
+pub fn apply_atlas(
+    gltf: &mut Gltf,
+    buffers: &mut Buffers,
+    atlas: &Atlas,
+    buffer: &str,
+) -> Vec<String> {
+    // The packed image of each remapped material, and the skipped materials.
+    let mut remapped = HashMap::new();
+    let mut skipped = Vec::new();
+    for (id, material) in &gltf.materials {
+        let images: Vec<_> = material.textures(gltf)
+            .into_iter()
+            .map(|(_, texture)| gltf.textures[texture].source.as_str())
+            .collect();
+        if !images.iter().any(|image| atlas.regions.contains_key(*image)) {
+            continue;
+        }
+        let region = atlas.regions.get(images[0]);
+        if images.iter().all(|image| atlas.regions.get(*image) == region) {
+            remapped.insert(id.clone(), images[0].to_string());
+        } else {
+            skipped.push(id.clone());
+        }
+    }
+
+    // The attributes referring to each texture co-ordinate accessor, keyed by
+    // the accessor and the packed image it is remapped for.
+    let mut users: HashMap<(&str, &str), Vec<Attribute>> = HashMap::new();
+    for (mesh_id, mesh) in &gltf.meshes {
+        for (index, primitive) in mesh.primitives.iter().enumerate() {
+            let image = match remapped.get(&primitive.material) {
+                Some(image) => image,
+                None => continue,
+            };
+            for (name, accessor) in &primitive.attributes {
+                if let Semantic::TexCoord(_) = Semantic::from_attribute(name) {
+                    users.entry((accessor, image))
+                        .or_default()
+                        .push((mesh_id, index, name));
+                }
+            }
+        }
+    }
+    let mut remaps = Vec::new();
+    for ((accessor, image), users) in users {
+        let region = atlas.regions[image];
+        let view = gltf.accessors.get(accessor)
+            .and_then(|accessor| accessor.view(gltf, buffers));
+        if let Some(view) = view {
+            let uvs: Vec<[f32; 2]> = view.iter().map(|uv| region.map(uv)).collect();
+            let users: Vec<_> = users.into_iter()
+                .map(|(mesh, index, name)| (mesh.to_string(), index, name.to_string()))
+                .collect();
+            remaps.push((format!("{}_atlas", accessor), uvs, users));
+        }
+    }
+
+    let mut builder = Builder::new(gltf, buffers, buffer);
+    for (id, uvs, users) in remaps {
+        let id = builder.accessor(&id, &uvs, Some(Target::ArrayBuffer));
+        for (mesh, index, name) in users {
+            let mesh = builder.gltf().meshes.get_mut(&mesh).unwrap();
+            mesh.primitives[index].attributes.insert(name, id.clone());
+        }
+    }
+
+    let gltf = builder.gltf();
+    let mut atlas_textures = HashMap::new();
+    for material in remapped.keys() {
+        let textures: Vec<(String, String)> = gltf.materials[material].textures(gltf)
+            .into_iter()
+            .map(|(parameter, texture)| (parameter.to_string(), texture.to_string()))
+            .collect();
+        for (parameter, texture) in textures {
+            let atlas_texture = atlas_textures.entry(texture.clone())
+                .or_insert_with(|| {
+                    let mut copy = gltf.textures[&texture].clone();
+                    copy.source = atlas.image.clone();
+                    let id = format!("{}_atlas", texture);
+                    gltf.textures.insert(id.clone(), copy);
+                    id
+                })
+                .clone();
+            gltf.materials.get_mut(material).unwrap()
+                .values
+                .insert(parameter, Value::String(atlas_texture));
+        }
+    }
+    skipped.sort();
+    skipped
+}
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_remaps_tex_coords_into_regions() {
+        let mut gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "uvs": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 1, "type": "VEC2"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteLength": 8 }
+            },
+            "images": {
+                "a": { "uri": "a.png" },
+                "b": { "uri": "b.png" },
+                "atlas": { "uri": "atlas.png" }
+            },
+            "materials": {
+                "left": { "values": { "diffuse": "ta" } },
+                "right": { "values": { "diffuse": "tb" } },
+                "both": { "values": { "diffuse": "ta", "specular": "tb" } }
+            },
+            "meshes": {
+                "mesh": {
+                    "primitives": [
+                        { "attributes": { "TEXCOORD_0": "uvs" }, "material": "left" },
+                        { "attributes": { "TEXCOORD_0": "uvs" }, "material": "right" },
+                        { "attributes": { "TEXCOORD_0": "uvs" }, "material": "both" }
+                    ]
+                }
+            },
+            "textures": {
+                "ta": { "source": "a", "sampler": "sampler" },
+                "tb": { "source": "b", "sampler": "sampler" }
+            }
+        }"#).unwrap();
+        let mut buffers = Buffers::new();
+        buffers.insert("buffer".to_string(),
+                       [0.5f32, 1.0].iter().flat_map(|x| x.to_le_bytes()).collect());
+        let mut regions = HashMap::new();
+        regions.insert("a".to_string(), Region { offset: [0.0, 0.0], scale: [0.5, 0.5] });
+        regions.insert("b".to_string(), Region { offset: [0.5, 0.5], scale: [0.5, 0.5] });
+        let atlas = Atlas { image: "atlas".to_string(), regions };
+
+        let skipped = apply_atlas(&mut gltf, &mut buffers, &atlas, "atlas_data");
+        assert_eq!(vec!["both".to_string()], skipped);
+        assert_eq!("atlas", gltf.textures["ta_atlas"].source);
+        assert_eq!(Some("ta_atlas"), gltf.materials["left"].values["diffuse"].as_str());
+        assert_eq!(Some("ta"), gltf.materials["both"].values["diffuse"].as_str());
+
+        let read = |primitive: usize| -> Vec<[f32; 2]> {
+            let id = &gltf.meshes["mesh"].primitives[primitive].attributes["TEXCOORD_0"];
+            gltf.accessors[id].view(&gltf, &buffers).unwrap().iter().collect()
+        };
+        assert_eq!(vec![[0.25, 0.5]], read(0));
+        assert_eq!(vec![[0.75, 1.0]], read(1));
+        assert_eq!(vec![[0.5, 1.0]], read(2));
+    }
+}
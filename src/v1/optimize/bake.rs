@@ -0,0 +1,403 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::{HashMap, HashSet};
+use v1::Gltf;
+use v1::accessor::{ComponentType, Kind};
+use v1::buffer::{Buffers, Target};
+use v1::builder::Builder;
+use v1::math;
+use v1::mesh::{Mesh, Mode, Semantic};
+use v1::node::{Hierarchy, Node};
+
+/// Multiplies the local transform of every static node into the vertex data
+/// of its meshes and children, then resets the node to the identity.
+///
+/// A node is static when no animation targets it, and it is neither a joint,
+/// a skinned node, nor a camera holder. Its transform is only baked when no
+/// animation targets its children either, since they absorb it. Each baked
+/// mesh is copied to `<mesh>_<node>` with new accessors appended to `buffer`,
+/// so meshes instanced elsewhere are unaffected. Returns the sorted IDs of
+/// the nodes reset to the identity.
+pub fn bake_transforms(
+    gltf: &mut Gltf,
+    buffers: &mut Buffers,
+    buffer: &str,
+) -> Vec<String> {
+    let animated: HashSet<String> = gltf.animations.values()
+        .flat_map(|animation| &animation.channels)
+        .map(|channel| channel.target.id.clone())
+        .collect();
+    let hierarchy = Hierarchy::new(gltf);
+    let mut pending: Vec<String> = gltf.nodes.keys()
+        .filter(|id| hierarchy.is_root(id))
+        .cloned()
+        .collect();
+    pending.sort_by(|a, b| b.cmp(a));
+
+    let mut visited = HashSet::new();
+    let mut baked = Vec::new();
+    while let Some(id) = pending.pop() {
+        if !visited.insert(id.clone()) {
+            continue;
+        }
+        let (matrix, children, meshes) = match gltf.nodes.get(&id) {
+            Some(node) => (node.local_matrix(), node.children.clone(), node.meshes.clone()),
+            None => continue,
+        };
+        pending.extend(children.iter().rev().cloned());
+        let mirrored = math::determinant3(&matrix) < 0.0;
+        let bakeable = is_static(&gltf.nodes[&id], &id, &animated)
+            && !children.iter().any(|child| animated.contains(child))
+            && matrix != math::IDENTITY
+            && meshes.iter()
+                .filter_map(|mesh| gltf.meshes.get(mesh))
+                .all(|mesh| is_transformable(gltf, buffers, mesh, mirrored));
+        if !bakeable {
+            continue;
+        }
+
+        let mut baked_meshes = Vec::with_capacity(meshes.len());
+        for mesh_id in meshes {
+            let mesh = match gltf.meshes.get(&mesh_id) {
+                Some(mesh) => mesh.clone(),
+                None => {
+                    baked_meshes.push(mesh_id);
+                    continue;
+                },
+            };
+            let suffix = id.clone();
+            let mut transformed = HashMap::new();
+            let mesh = transform_mesh(gltf, buffers, buffer, mesh, &matrix, &suffix,
+                                      &mut transformed);
+            let new_id = unique_mesh_id(gltf, &format!("{}_{}", mesh_id, suffix));
+            gltf.meshes.insert(new_id.clone(), mesh);
+            baked_meshes.push(new_id);
+        }
+        for child in &children {
+            if let Some(child) = gltf.nodes.get_mut(child) {
+                let local = math::mul(&matrix, &child.local_matrix());
+                set_matrix(child, local);
+            }
+        }
+        let node = gltf.nodes.get_mut(&id).unwrap();
+        node.meshes = baked_meshes;
+        node.matrix = None;
+        node.translation = None;
+        node.rotation = None;
+        node.scale = None;
+        baked.push(id);
+    }
+    baked.sort();
+    baked
+}
+
+/// Returns whether a node's transform may be changed without affecting
+/// animation, skinning, or cameras.
+fn is_static(node: &Node, id: &str, animated: &HashSet<String>) -> bool {
+    !animated.contains(id)
+        && node.skin.is_none()
+        && node.joint_name.is_none()
+        && node.camera.is_none()
+        && node.skeletons.is_empty()
+}
+
+/// Returns whether every `POSITION`, `NORMAL`, and `TANGENT` attribute of a
+/// mesh can be rewritten by `transform_mesh`, and, when the transform
+/// mirrors, the indices of every triangle primitive can be read.
+fn is_transformable(gltf: &Gltf, buffers: &Buffers, mesh: &Mesh, mirrored: bool) -> bool {
+    mesh.primitives.iter().all(|primitive| {
+        let attributes = primitive.attributes.iter().all(|(name, id)| {
+            let semantic = Semantic::from_attribute(name);
+            let is_tangent = semantic == Semantic::Extra("TANGENT".to_string());
+            let vertex = matches!(semantic, Semantic::Position | Semantic::Normal);
+            if !vertex && !is_tangent {
+                return true;
+            }
+            let accessor = match gltf.accessors.get(id) {
+                Some(accessor) => accessor,
+                None => return false,
+            };
+            let kind = match accessor.kind {
+                Kind::Vec3 => true,
+                Kind::Vec4 => is_tangent,
+                _ => false,
+            };
+            kind && accessor.component_type == ComponentType::F32
+                && accessor.view(gltf, buffers).is_some()
+        });
+        let triangles = matches!(primitive.mode,
+                                 Mode::Triangles | Mode::TriangleStrip | Mode::TriangleFan);
+        let indices = !mirrored || !triangles
+            || primitive.iter_indices(gltf, buffers).is_some();
+        attributes && indices
+    })
+}
+
+/// Replaces the local transform of a node with a matrix.
+fn set_matrix(node: &mut Node, matrix: [f32; 16]) {
+    node.matrix = Some(matrix);
+    node.translation = None;
+    node.rotation = None;
+    node.scale = None;
+}
+
+/// Returns `prefix`, or `prefix` with the smallest numeric suffix that makes
+/// it unused by any mesh.
+fn unique_mesh_id(gltf: &Gltf, prefix: &str) -> String {
+    if !gltf.meshes.contains_key(prefix) {
+        return prefix.to_string();
+    }
+    (1..)
+        .map(|n| format!("{}_{}", prefix, n))
+        .find(|id| !gltf.meshes.contains_key(id))
+        .unwrap()
+}
+
+/// Transforms the vertex data of a mesh by `matrix`, writing the results to
+/// new `<accessor>_<suffix>` accessors appended to `buffer`.
+///
+/// Float `POSITION`, `NORMAL`, and `TANGENT` attributes are transformed;
+/// other attributes are kept. When the matrix mirrors, the triangles of each
+/// primitive are rewritten as a list with reversed winding, and the `w` of
+/// every tangent is negated so that bitangents stay correct. `transformed`
+/// maps the IDs of accessors already rewritten with the same matrix to their
+/// replacements, and is extended with the accessors rewritten here.
+pub(crate) fn transform_mesh(
+    gltf: &mut Gltf,
+    buffers: &mut Buffers,
+    buffer: &str,
+    mut mesh: Mesh,
+    matrix: &[f32; 16],
+    suffix: &str,
+    transformed: &mut HashMap<String, String>,
+) -> Mesh {
+    let transform = Transform {
+        matrix: *matrix,
+        normal_matrix: math::normal_matrix(matrix).unwrap_or(math::IDENTITY),
+        mirrored: math::determinant3(matrix) < 0.0,
+    };
+    for primitive in &mut mesh.primitives {
+        for (name, id) in &mut primitive.attributes {
+            if let Some(new_id) = transformed.get(id) {
+                *id = new_id.clone();
+                continue;
+            }
+            let semantic = Semantic::from_attribute(name);
+            let new_id = transform_attribute(gltf, buffers, buffer, id, &semantic,
+                                             &transform, suffix);
+            if let Some(new_id) = new_id {
+                transformed.insert(id.clone(), new_id.clone());
+                *id = new_id;
+            }
+        }
+
+        let triangles = matches!(primitive.mode,
+                                 Mode::Triangles | Mode::TriangleStrip | Mode::TriangleFan);
+        if !transform.mirrored || !triangles {
+            continue;
+        }
+        // Reversed strips and fans depend on the mode, so only lists are
+        // shared between primitives.
+        let list = primitive.mode == Mode::Triangles;
+        let cached = primitive.indices.as_ref()
+            .filter(|_| list)
+            .and_then(|id| transformed.get(id))
+            .cloned();
+        if let Some(id) = cached {
+            primitive.indices = Some(id);
+            continue;
+        }
+        let indices = match primitive.read_indices(gltf, buffers) {
+            Some(indices) => indices,
+            None => continue,
+        };
+        let reversed: Vec<u32> = primitive.triangles(indices)
+            .flat_map(|[a, b, c]| [a, c, b])
+            .collect();
+        let id = match primitive.indices {
+            Some(ref id) => format!("{}_{}", id, suffix),
+            None => format!("indices_{}", suffix),
+        };
+        let new_id = Builder::new(gltf, buffers, buffer).indices(&id, &reversed);
+        if let (true, Some(id)) = (list, primitive.indices.take()) {
+            transformed.insert(id, new_id.clone());
+        }
+        primitive.indices = Some(new_id);
+        primitive.mode = Mode::Triangles;
+    }
+    mesh
+}
+
+/// A transform applied to vertex data.
+struct Transform {
+    /// The matrix applied to positions and tangents.
+    matrix: [f32; 16],
+
+    /// The matrix applied to normals.
+    normal_matrix: [f32; 16],
+
+    /// Whether the matrix mirrors, reversing triangle winding.
+    mirrored: bool,
+}
+
+/// Transforms a single float attribute accessor, returning the ID of the new
+/// accessor or `None` if the attribute is kept as it is.
+fn transform_attribute(
+    gltf: &mut Gltf,
+    buffers: &mut Buffers,
+    buffer: &str,
+    id: &str,
+    semantic: &Semantic,
+    transform: &Transform,
+    suffix: &str,
+) -> Option<String> {
+    let Transform { ref matrix, ref normal_matrix, mirrored } = *transform;
+    let accessor = gltf.accessors.get(id)?;
+    if accessor.component_type != ComponentType::F32 {
+        return None;
+    }
+    let view = accessor.view(gltf, buffers)?;
+    let new_id = format!("{}_{}", id, suffix);
+    let target = Some(Target::ArrayBuffer);
+    let is_tangent = *semantic == Semantic::Extra("TANGENT".to_string());
+    match (semantic, accessor.kind) {
+        (&Semantic::Position, Kind::Vec3) => {
+            let positions: Vec<[f32; 3]> = view.iter()
+                .map(|position| math::transform_point(matrix, position))
+                .collect();
+            Some(Builder::new(gltf, buffers, buffer).accessor(&new_id, &positions, target))
+        },
+        (&Semantic::Normal, Kind::Vec3) => {
+            let normals: Vec<[f32; 3]> = view.iter()
+                .map(|normal| math::transform_vector(normal_matrix, normal))
+                .map(math::normalize)
+                .collect();
+            Some(Builder::new(gltf, buffers, buffer).accessor(&new_id, &normals, target))
+        },
+        (_, Kind::Vec3) if is_tangent => {
+            let tangents: Vec<[f32; 3]> = view.iter()
+                .map(|tangent| math::transform_vector(matrix, tangent))
+                .map(math::normalize)
+                .collect();
+            Some(Builder::new(gltf, buffers, buffer).accessor(&new_id, &tangents, target))
+        },
+        (_, Kind::Vec4) if is_tangent => {
+            let tangents: Vec<[f32; 4]> = view.iter()
+                .map(|[x, y, z, w]: [f32; 4]| {
+                    let xyz = math::transform_vector(matrix, [x, y, z]);
+                    let [x, y, z] = math::normalize(xyz);
+                    [x, y, z, if mirrored { -w } else { w }]
+                })
+                .collect();
+            Some(Builder::new(gltf, buffers, buffer).accessor(&new_id, &tangents, target))
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_bakes_static_transforms() {
+        let mut gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "positions": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 3, "type": "VEC3"
+                }
+            },
+            "animations": {
+                "spin": {
+                    "channels": [{
+                        "sampler": "sampler",
+                        "target": { "id": "spinning", "path": "rotation" }
+                    }],
+                    "samplers": {
+                        "sampler": { "input": "time", "output": "rotation" }
+                    }
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteLength": 36 }
+            },
+            "meshes": {
+                "triangle": {
+                    "primitives": [{
+                        "attributes": { "POSITION": "positions" },
+                        "material": "material"
+                    }]
+                }
+            },
+            "nodes": {
+                "moved": {
+                    "meshes": ["triangle"],
+                    "children": ["child"],
+                    "translation": [0, 0, 1]
+                },
+                "child": { "meshes": ["triangle"], "scale": [-1, 1, 1] },
+                "spinning": { "meshes": ["triangle"], "translation": [1, 0, 0] }
+            },
+            "scenes": {
+                "scene": { "nodes": ["moved", "spinning"] }
+            }
+        }"#).unwrap();
+        let positions: [f32; 9] = [-1.0, -1.0, 0.0, 1.0, -1.0, 0.0, 0.0, 1.0, 0.0];
+        let mut buffers = Buffers::new();
+        buffers.insert("buffer".to_string(),
+                       positions.iter().flat_map(|x| x.to_le_bytes()).collect());
+        let before = gltf.scenes["scene"].flatten(&gltf, &buffers);
+
+        let baked = bake_transforms(&mut gltf, &mut buffers, "baked");
+        assert_eq!(vec!["child", "moved"], baked);
+        assert_eq!(math::IDENTITY, gltf.nodes["child"].local_matrix());
+        assert_eq!(vec!["triangle_child"], gltf.nodes["child"].meshes);
+        assert_eq!(vec!["triangle"], gltf.nodes["spinning"].meshes);
+        let mirrored = &gltf.meshes["triangle_child"].primitives[0];
+        assert_eq!(Some(vec![0, 2, 1]), mirrored.read_indices(&gltf, &buffers));
+        assert_eq!(before, gltf.scenes["scene"].flatten(&gltf, &buffers));
+    }
+
+    #[test]
+    fn it_keeps_nodes_with_unreadable_attributes() {
+        let mut gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "positions": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 3, "type": "VEC3"
+                },
+                "normals": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5122, "count": 3, "type": "VEC3"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteLength": 36 }
+            },
+            "meshes": {
+                "triangle": {
+                    "primitives": [{
+                        "attributes": { "POSITION": "positions", "NORMAL": "normals" },
+                        "material": "material"
+                    }]
+                }
+            },
+            "nodes": {
+                "moved": { "meshes": ["triangle"], "translation": [0, 0, 1] }
+            }
+        }"#).unwrap();
+        let mut buffers = Buffers::new();
+        buffers.insert("buffer".to_string(), vec![0; 36].into());
+        assert!(bake_transforms(&mut gltf, &mut buffers, "baked").is_empty());
+        assert_eq!(Some([0.0, 0.0, 1.0]), gltf.nodes["moved"].translation);
+        assert_eq!(vec!["triangle"], gltf.nodes["moved"].meshes);
+    }
+}
@@ -0,0 +1,326 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::HashMap;
+use v1::Gltf;
+use v1::animation::TargetPath;
+use v1::buffer::Buffers;
+use v1::builder::Builder;
+use v1::math;
+use v1::node::Node;
+use super::bake::transform_mesh;
+
+/// The axis pointing up in a right-handed coordinate system.
+///
+/// glTF assets are Y-up.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Up {
+    /// +Y is up and +Z points forward, as in glTF.
+    Y,
+
+    /// +Z is up and -Y points forward.
+    Z,
+}
+
+/// Returns the matrix converting co-ordinates from a Y-up system to the given
+/// system, scaling them by `scale`.
+///
+/// For example, a `scale` of `100.0` converts metres, the unit of glTF, to
+/// centimetres.
+pub fn conversion_matrix(up: Up, scale: f32) -> [f32; 16] {
+    let s = scale;
+    match up {
+        Up::Y => [
+            s, 0.0, 0.0, 0.0,
+            0.0, s, 0.0, 0.0,
+            0.0, 0.0, s, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ],
+        // A quarter turn about +X maps +Y to +Z and +Z to -Y.
+        Up::Z => [
+            s, 0.0, 0.0, 0.0,
+            0.0, 0.0, s, 0.0,
+            0.0, -s, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ],
+    }
+}
+
+/// Converts every scene to the given up axis and unit scale by parenting its
+/// root nodes to a new node holding the conversion matrix.
+///
+/// The vertex data is unchanged, so the conversion is cheap and applies to
+/// cameras, lights, and animated nodes alike; `bake_transforms` can fold the
+/// new node into static meshes afterwards. Each scene gets a root node of its
+/// own, with the ID `<scene>_root` or that ID with a numeric suffix. Returns
+/// the sorted IDs of the new nodes.
+pub fn convert_coordinates(gltf: &mut Gltf, up: Up, scale: f32) -> Vec<String> {
+    let matrix = conversion_matrix(up, scale);
+    let mut scenes: Vec<String> = gltf.scenes.keys().cloned().collect();
+    scenes.sort();
+    let mut roots = Vec::with_capacity(scenes.len());
+    for scene_id in scenes {
+        let id = unique_node_id(gltf, &format!("{}_root", scene_id));
+        let scene = gltf.scenes.get_mut(&scene_id).unwrap();
+        let node = Node {
+            children: scene.nodes.clone(),
+            matrix: if matrix == math::IDENTITY { None } else { Some(matrix) },
+            ..Default::default()
+        };
+        scene.nodes = vec![id.clone()];
+        gltf.nodes.insert(id.clone(), node);
+        roots.push(id);
+    }
+    roots.sort();
+    roots
+}
+
+/// A co-ordinate axis.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Axis {
+    /// The X axis.
+    X,
+
+    /// The Y axis.
+    Y,
+
+    /// The Z axis.
+    Z,
+}
+
+/// Mirrors the whole asset along the given axis, converting it between
+/// right-handed and left-handed co-ordinates.
+///
+/// Vertex positions, normals, and tangents are mirrored, triangle winding is
+/// reversed so that front faces stay front-facing, and the `w` of every
+/// tangent is negated. Node transforms, inverse-bind and bind shape matrices,
+/// and translation and rotation keyframes are mirrored to match, so the
+/// asset stays valid and animates as before. New accessors with the suffix
+/// `_flipped` are appended to `buffer`, and every reference is updated.
+pub fn flip_handedness(
+    gltf: &mut Gltf,
+    buffers: &mut Buffers,
+    buffer: &str,
+    axis: Axis,
+) {
+    let mut signs = [1.0; 3];
+    signs[axis as usize] = -1.0;
+    let mut mirror = math::IDENTITY;
+    for (i, &sign) in signs.iter().enumerate() {
+        mirror[i * 5] = sign;
+    }
+
+    let mut transformed = HashMap::new();
+    let mut meshes: Vec<String> = gltf.meshes.keys().cloned().collect();
+    meshes.sort();
+    for id in meshes {
+        let mesh = gltf.meshes[&id].clone();
+        let mesh = transform_mesh(gltf, buffers, buffer, mesh, &mirror, "flipped",
+                                  &mut transformed);
+        gltf.meshes.insert(id, mesh);
+    }
+
+    for node in gltf.nodes.values_mut() {
+        node.matrix = node.matrix.map(|matrix| mirror_matrix(&matrix, signs));
+        node.translation = node.translation.map(|t| mirror_vector(t, signs));
+        node.rotation = node.rotation.map(|r| mirror_rotation(r, signs));
+    }
+
+    let mut skins: Vec<String> = gltf.skins.keys().cloned().collect();
+    skins.sort();
+    for id in skins {
+        if let Some(accessor) = gltf.skins[&id].inverse_bind_matrices.clone() {
+            let new_id = match transformed.get(&accessor) {
+                Some(new_id) => Some(new_id.clone()),
+                None => gltf.skins[&id].read_inverse_bind_matrices(gltf, buffers)
+                    .map(|matrices| {
+                        let matrices: Vec<_> = matrices.iter()
+                            .map(|matrix| mirror_matrix(matrix, signs))
+                            .collect();
+                        let new_id = Builder::new(gltf, buffers, buffer)
+                            .accessor(&format!("{}_flipped", accessor), &matrices, None);
+                        transformed.insert(accessor, new_id.clone());
+                        new_id
+                    }),
+            };
+            if new_id.is_some() {
+                gltf.skins.get_mut(&id).unwrap().inverse_bind_matrices = new_id;
+            }
+        }
+        let skin = gltf.skins.get_mut(&id).unwrap();
+        skin.bind_shape_matrix = mirror_matrix(&skin.bind_shape_matrix, signs);
+    }
+
+    let mut animations: Vec<String> = gltf.animations.keys().cloned().collect();
+    animations.sort();
+    for id in animations {
+        let channels = gltf.animations[&id].channels.clone();
+        for channel in channels {
+            let (parameter, accessor) = {
+                let animation = &gltf.animations[&id];
+                let parameter = match animation.samplers.get(&channel.sampler) {
+                    Some(sampler) => sampler.output.clone(),
+                    None => continue,
+                };
+                match animation.parameters.get(&parameter) {
+                    Some(accessor) => (parameter, accessor.clone()),
+                    None => continue,
+                }
+            };
+            let new_id = match transformed.get(&accessor) {
+                Some(new_id) => new_id.clone(),
+                None => {
+                    let view = match gltf.accessors.get(&accessor)
+                        .and_then(|a| a.view(gltf, buffers))
+                    {
+                        Some(view) => view,
+                        None => continue,
+                    };
+                    let new_id = format!("{}_flipped", accessor);
+                    let new_id = match channel.target.path {
+                        TargetPath::Translation => {
+                            let values: Vec<[f32; 3]> = view.iter()
+                                .map(|t| mirror_vector(t, signs))
+                                .collect();
+                            Builder::new(gltf, buffers, buffer)
+                                .accessor(&new_id, &values, None)
+                        },
+                        TargetPath::Rotation => {
+                            let values: Vec<[f32; 4]> = view.iter()
+                                .map(|r| mirror_rotation(r, signs))
+                                .collect();
+                            Builder::new(gltf, buffers, buffer)
+                                .accessor(&new_id, &values, None)
+                        },
+                        TargetPath::Scale => continue,
+                    };
+                    transformed.insert(accessor, new_id.clone());
+                    new_id
+                },
+            };
+            let animation = gltf.animations.get_mut(&id).unwrap();
+            animation.parameters.insert(parameter, new_id);
+        }
+    }
+}
+
+/// Conjugates a column-major matrix by the mirror with the given signs.
+fn mirror_matrix(matrix: &[f32; 16], signs: [f32; 3]) -> [f32; 16] {
+    let sign = |i: usize| if i < 3 { signs[i] } else { 1.0 };
+    let mut mirrored = *matrix;
+    for (i, value) in mirrored.iter_mut().enumerate() {
+        *value *= sign(i / 4) * sign(i % 4);
+    }
+    mirrored
+}
+
+/// Mirrors a vector.
+fn mirror_vector(v: [f32; 3], signs: [f32; 3]) -> [f32; 3] {
+    [v[0] * signs[0], v[1] * signs[1], v[2] * signs[2]]
+}
+
+/// Mirrors a unit quaternion: the rotation axis component along the mirrored
+/// axis is kept and the others are negated.
+fn mirror_rotation(r: [f32; 4], signs: [f32; 3]) -> [f32; 4] {
+    [-r[0] * signs[0], -r[1] * signs[1], -r[2] * signs[2], r[3]]
+}
+
+/// Returns `prefix`, or `prefix` with the smallest numeric suffix that makes
+/// it unused by any node.
+fn unique_node_id(gltf: &Gltf, prefix: &str) -> String {
+    if !gltf.nodes.contains_key(prefix) {
+        return prefix.to_string();
+    }
+    (1..)
+        .map(|n| format!("{}_{}", prefix, n))
+        .find(|id| !gltf.nodes.contains_key(id))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_converts_to_z_up_centimetres() {
+        let mut gltf: Gltf = serde_json::from_str(r#"{
+            "nodes": {
+                "scene_root": {},
+                "head": { "translation": [0, 2, 1] }
+            },
+            "scenes": {
+                "scene": { "nodes": ["head", "scene_root"] }
+            }
+        }"#).unwrap();
+        let roots = convert_coordinates(&mut gltf, Up::Z, 100.0);
+        assert_eq!(vec!["scene_root_1"], roots);
+        assert_eq!(vec!["scene_root_1"], gltf.scenes["scene"].nodes);
+        assert_eq!(vec!["head", "scene_root"], gltf.nodes["scene_root_1"].children);
+
+        let head = gltf.scenes["scene"].traverse(&gltf)
+            .find(|visit| visit.id == "head")
+            .unwrap();
+        let position = math::transform_point(&head.transform, [0.0, 0.0, 0.0]);
+        assert_eq!([0.0, -100.0, 200.0], position);
+    }
+
+    #[test]
+    fn it_flips_handedness() {
+        let mut gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "positions": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 3, "type": "VEC3"
+                },
+                "tangents": {
+                    "bufferView": "view", "byteOffset": 36,
+                    "componentType": 5126, "count": 3, "type": "VEC4"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteLength": 84 }
+            },
+            "meshes": {
+                "triangle": {
+                    "primitives": [{
+                        "attributes": { "POSITION": "positions", "TANGENT": "tangents" },
+                        "material": "material"
+                    }]
+                }
+            },
+            "nodes": {
+                "node": {
+                    "meshes": ["triangle"],
+                    "translation": [1, 2, 3],
+                    "rotation": [0.6, 0, 0, 0.8]
+                }
+            }
+        }"#).unwrap();
+        let data: [f32; 21] = [
+            0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0,
+            1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0,
+        ];
+        let mut buffers = Buffers::new();
+        buffers.insert("buffer".to_string(),
+                       data.iter().flat_map(|x| x.to_le_bytes()).collect());
+
+        flip_handedness(&mut gltf, &mut buffers, "flipped", Axis::Z);
+        let node = &gltf.nodes["node"];
+        assert_eq!(Some([1.0, 2.0, -3.0]), node.translation);
+        assert_eq!(Some([-0.6, 0.0, 0.0, 0.8]), node.rotation);
+
+        let primitive = &gltf.meshes["triangle"].primitives[0];
+        assert_eq!(Some(vec![0, 2, 1]), primitive.read_indices(&gltf, &buffers));
+        let tangents = &gltf.accessors[&primitive.attributes["TANGENT"]];
+        let tangents: Vec<[f32; 4]> = tangents.view(&gltf, &buffers).unwrap()
+            .iter()
+            .collect();
+        assert_eq!(vec![[1.0, 0.0, 0.0, -1.0]; 3], tangents);
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use v1::Gltf;
+use v1::accessor::{Accessor, MAX_BYTE_STRIDE};
+use v1::buffer::{Buffers, Target};
+use v1::builder::{self, Builder};
+use v1::mesh::Semantic;
+
+/// Packs attributes of a primitive into a single interleaved bufferView
+/// appended to `buffer`, and returns the ID of the bufferView.
+///
+/// The attributes are stored in each vertex in the order of `layout`, each
+/// starting at a multiple of four bytes. The primitive is patched to use new
+/// `<id>_interleaved` accessors; attributes not in `layout` are unchanged.
+/// Returns `None`, changing nothing, if the primitive or one of the
+/// attributes is missing or unreadable, the attributes differ in length, or
+/// the vertex would be larger than glTF allows.
+pub fn interleave(
+    gltf: &mut Gltf,
+    buffers: &mut Buffers,
+    buffer: &str,
+    mesh: &str,
+    primitive: usize,
+    layout: &[Semantic],
+) -> Option<String> {
+    let mut attributes = Vec::new();
+    let mut stride = 0;
+    {
+        let primitive = gltf.meshes.get(mesh)?.primitives.get(primitive)?;
+        for semantic in layout {
+            let (name, id) = primitive.attributes.iter()
+                .find(|&(name, _)| Semantic::from_attribute(name) == *semantic)?;
+            let accessor = gltf.accessors.get(id)?;
+            let data = accessor.view(gltf, buffers)?.packed();
+            attributes.push((name.clone(), id.clone(), stride, data));
+            stride += accessor.element_size().div_ceil(4) * 4;
+        }
+    }
+    let count = match attributes.first() {
+        Some((_, id, _, _)) => gltf.accessors[id].count as usize,
+        None => return None,
+    };
+    let lengths_match = attributes.iter()
+        .all(|(_, id, _, _)| gltf.accessors[id].count as usize == count);
+    if stride > MAX_BYTE_STRIDE as usize || !lengths_match {
+        return None;
+    }
+
+    let mut data = vec![0; count * stride];
+    for (_, id, offset, packed) in &attributes {
+        let size = gltf.accessors[id].element_size();
+        for (vertex, element) in packed.chunks(size).enumerate() {
+            let start = vertex * stride + offset;
+            data[start..start + size].copy_from_slice(element);
+        }
+    }
+    let view = Builder::new(gltf, buffers, buffer).buffer_view(
+        &format!("{}_{}_interleaved", mesh, primitive),
+        &data,
+        Some(Target::ArrayBuffer),
+    );
+    for (name, id, offset, _) in attributes {
+        let accessor = {
+            let original = &gltf.accessors[&id];
+            Accessor {
+                buffer_view: view.clone(),
+                byte_offset: offset as u32,
+                byte_stride: stride as u32,
+                component_type: original.component_type,
+                count: original.count,
+                kind: original.kind,
+                min: original.min.clone(),
+                max: original.max.clone(),
+                ..Default::default()
+            }
+        };
+        let new_id = builder::unique_id(gltf, &format!("{}_interleaved", id));
+        gltf.accessors.insert(new_id.clone(), accessor);
+        let primitives = &mut gltf.meshes.get_mut(mesh).unwrap().primitives;
+        primitives[primitive].attributes.insert(name, new_id);
+    }
+    Some(view)
+}
+
+/// Moves the data of every interleaved accessor to a tightly packed
+/// bufferView of its own, appended to `buffer`.
+///
+/// An accessor is interleaved when its byte stride exceeds its element size.
+/// Since the element values do not change, such accessors are patched in
+/// place and keep their IDs. Accessors whose data cannot be read are left
+/// alone. Returns the sorted IDs of the patched accessors.
+pub fn deinterleave(gltf: &mut Gltf, buffers: &mut Buffers, buffer: &str) -> Vec<String> {
+    let mut packed = Vec::new();
+    for (id, accessor) in &gltf.accessors {
+        if accessor.stride() <= accessor.element_size() {
+            continue;
+        }
+        let view = match accessor.view(gltf, buffers) {
+            Some(view) => view,
+            None => continue,
+        };
+        let target = gltf.buffer_views[&accessor.buffer_view].target;
+        packed.push((id.clone(), view.packed(), target));
+    }
+    packed.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut builder = Builder::new(gltf, buffers, buffer);
+    let mut ids = Vec::new();
+    for (id, data, target) in packed {
+        let view = builder.buffer_view(&format!("{}_packed", id), &data, target);
+        let accessor = builder.gltf().accessors.get_mut(&id).unwrap();
+        accessor.buffer_view = view;
+        accessor.byte_offset = 0;
+        accessor.byte_stride = 0;
+        ids.push(id);
+    }
+    ids
+}
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_deinterleaves_accessors() {
+        let mut gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "positions": {
+                    "bufferView": "vertices", "byteOffset": 0, "byteStride": 8,
+                    "componentType": 5126, "count": 2, "type": "SCALAR"
+                },
+                "weights": {
+                    "bufferView": "vertices", "byteOffset": 4, "byteStride": 8,
+                    "componentType": 5126, "count": 2, "type": "SCALAR"
+                },
+                "packed": {
+                    "bufferView": "vertices", "byteOffset": 0,
+                    "componentType": 5126, "count": 4, "type": "SCALAR"
+                }
+            },
+            "bufferViews": {
+                "vertices": { "buffer": "buffer", "byteLength": 16, "target": 34962 }
+            }
+        }"#).unwrap();
+        let mut buffers = Buffers::new();
+        let data = [1.0f32, 0.5, 2.0, 0.25];
+        buffers.insert("buffer".to_string(),
+                       data.iter().flat_map(|x| x.to_le_bytes()).collect());
+
+        let ids = deinterleave(&mut gltf, &mut buffers, "soa");
+        assert_eq!(vec!["positions", "weights"], ids);
+        for (id, expected) in &[("positions", [1.0, 2.0]), ("weights", [0.5, 0.25])] {
+            let accessor = &gltf.accessors[*id];
+            assert_eq!(0, accessor.byte_stride);
+            assert_eq!("soa", gltf.buffer_views[&accessor.buffer_view].buffer);
+            let read: Vec<f32> = accessor.view(&gltf, &buffers).unwrap().iter().collect();
+            assert_eq!(&expected[..], &read[..]);
+        }
+        assert_eq!("vertices", gltf.accessors["packed"].buffer_view);
+    }
+
+    #[test]
+    fn it_interleaves_attributes() {
+        let mut gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "positions": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 2, "type": "VEC2"
+                },
+                "colors": {
+                    "bufferView": "view", "byteOffset": 16,
+                    "componentType": 5121, "count": 2, "type": "VEC3"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteLength": 22 }
+            },
+            "meshes": {
+                "mesh": {
+                    "primitives": [{
+                        "attributes": { "POSITION": "positions", "COLOR_0": "colors" },
+                        "material": "material"
+                    }]
+                }
+            }
+        }"#).unwrap();
+        let mut data: Vec<u8> = [1.0f32, 2.0, 3.0, 4.0].iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        data.extend_from_slice(&[10, 20, 30, 40, 50, 60]);
+        let mut buffers = Buffers::new();
+        buffers.insert("buffer".to_string(), data.into());
+
+        let layout = [Semantic::Color(0), Semantic::Position];
+        let view = interleave(&mut gltf, &mut buffers, "aos", "mesh", 0, &layout).unwrap();
+        assert_eq!(24, gltf.buffer_views[&view].byte_length);
+        let primitive = &gltf.meshes["mesh"].primitives[0];
+        let colors = &gltf.accessors[primitive.attribute(&Semantic::Color(0)).unwrap()];
+        let positions = &gltf.accessors[primitive.attribute(&Semantic::Position).unwrap()];
+        assert_eq!((0, 12), (colors.byte_offset, colors.byte_stride));
+        assert_eq!((4, 12), (positions.byte_offset, positions.byte_stride));
+        let read: Vec<[f32; 2]> = positions.view(&gltf, &buffers).unwrap().iter().collect();
+        assert_eq!(vec![[1.0, 2.0], [3.0, 4.0]], read);
+        let read: Vec<[u32; 4]> = colors.view(&gltf, &buffers).unwrap().iter().collect();
+        assert_eq!(vec![[10, 20, 30, 0], [40, 50, 60, 0]], read);
+        assert!(interleave(&mut gltf, &mut buffers, "aos", "mesh", 0, &[Semantic::Normal])
+                .is_none());
+    }
+}
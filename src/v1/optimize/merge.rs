@@ -0,0 +1,210 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::HashMap;
+use v1::Gltf;
+use v1::accessor::{ComponentType, Kind};
+use v1::buffer::{Buffers, Target};
+use v1::builder::Builder;
+use v1::mesh::{Mesh, Mode, Primitive};
+
+/// The properties primitives must share to be drawn with a single call.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct Key {
+    material: String,
+    attributes: Vec<(String, ComponentType, Kind)>,
+}
+
+/// Returns the groups of primitives of a mesh that `merge_primitives` would
+/// merge into one, as primitive indices in ascending order.
+///
+/// Primitives can be merged when they render triangles with the same material
+/// and have the same attributes with the same component types and kinds.
+/// Only groups of at least two primitives are returned, ordered by their
+/// first primitive.
+pub fn batches(gltf: &Gltf, mesh: &Mesh) -> Vec<Vec<usize>> {
+    let mut groups: HashMap<Key, Vec<usize>> = HashMap::new();
+    for (index, primitive) in mesh.primitives.iter().enumerate() {
+        if let Some(key) = key(gltf, primitive) {
+            groups.entry(key).or_default().push(index);
+        }
+    }
+    let mut batches: Vec<_> = groups.into_values()
+        .filter(|batch| batch.len() > 1)
+        .collect();
+    batches.sort();
+    batches
+}
+
+fn key(gltf: &Gltf, primitive: &Primitive) -> Option<Key> {
+    match primitive.mode {
+        Mode::Triangles | Mode::TriangleStrip | Mode::TriangleFan => {},
+        Mode::Points | Mode::Lines | Mode::LineLoop | Mode::LineStrip => return None,
+    }
+    let mut attributes = Vec::with_capacity(primitive.attributes.len());
+    for (name, id) in &primitive.attributes {
+        let accessor = gltf.accessors.get(id)?;
+        attributes.push((name.clone(), accessor.component_type, accessor.kind));
+    }
+    attributes.sort_by(|a, b| a.0.cmp(&b.0));
+    Some(Key {
+        material: primitive.material.clone(),
+        attributes,
+    })
+}
+
+/// Merges the primitives of every mesh that can be drawn with a single call,
+/// as reported by `batches`, and returns the number of primitives removed.
+///
+/// The vertex data of each group is concatenated into new accessors appended
+/// to `buffer`, and its triangles into a single list whose indices are
+/// offset accordingly. The merged primitive takes the place of the first
+/// primitive of the group. Groups whose data cannot be read are left alone.
+/// Meshes instanced by the same node are not merged with each other; bake
+/// their node transforms into separate meshes first when that is needed.
+pub fn merge_primitives(gltf: &mut Gltf, buffers: &mut Buffers, buffer: &str) -> usize {
+    let mut ids: Vec<String> = gltf.meshes.keys().cloned().collect();
+    ids.sort();
+    let mut removed = 0;
+    for id in ids {
+        let mesh_batches = batches(gltf, &gltf.meshes[&id]);
+        let mut merged = Vec::new();
+        for (n, batch) in mesh_batches.into_iter().enumerate() {
+            let prefix = format!("{}_merged_{}", id, n);
+            if let Some(primitive) = merge(gltf, buffers, buffer, &id, &batch, &prefix) {
+                merged.push((batch, primitive));
+            }
+        }
+        let mesh = gltf.meshes.get_mut(&id).unwrap();
+        let mut remove = vec![false; mesh.primitives.len()];
+        for (batch, primitive) in merged {
+            mesh.primitives[batch[0]] = primitive;
+            for &index in &batch[1..] {
+                remove[index] = true;
+            }
+            removed += batch.len() - 1;
+        }
+        let mut index = 0;
+        mesh.primitives.retain(|_| {
+            index += 1;
+            !remove[index - 1]
+        });
+    }
+    removed
+}
+
+/// Concatenates the data of a batch of compatible primitives.
+fn merge(
+    gltf: &mut Gltf,
+    buffers: &mut Buffers,
+    buffer: &str,
+    mesh_id: &str,
+    batch: &[usize],
+    prefix: &str,
+) -> Option<Primitive> {
+    let mut attributes: Vec<(String, ComponentType, Kind, Vec<u8>)> = Vec::new();
+    let mut indices = Vec::new();
+    let mut base = 0;
+    for &index in batch {
+        let primitive = &gltf.meshes[mesh_id].primitives[index];
+        let mut names: Vec<&String> = primitive.attributes.keys().collect();
+        names.sort();
+        let mut count = None;
+        for (i, name) in names.into_iter().enumerate() {
+            let accessor = gltf.accessors.get(&primitive.attributes[name])?;
+            if *count.get_or_insert(accessor.count) != accessor.count {
+                return None;
+            }
+            let data = accessor.view(gltf, buffers)?.packed();
+            match attributes.get_mut(i) {
+                Some(attribute) => attribute.3.extend(data),
+                None => {
+                    attributes.push((name.clone(), accessor.component_type, accessor.kind,
+                                     data));
+                },
+            }
+        }
+        let primitive_indices = primitive.read_indices(gltf, buffers)?;
+        indices.extend(primitive.triangles(primitive_indices)
+            .flatten()
+            .map(|i| base + i));
+        base += count.unwrap_or(0);
+    }
+
+    let first = gltf.meshes[mesh_id].primitives[batch[0]].clone();
+    let mut builder = Builder::new(gltf, buffers, buffer);
+    let mut merged = Primitive {
+        attributes: HashMap::new(),
+        mode: Mode::Triangles,
+        ..first
+    };
+    for (name, component_type, kind, data) in attributes {
+        let id = format!("{}_{}", prefix, name.to_lowercase());
+        let id = builder.accessor_bytes(&id, component_type, kind, &data,
+                                        Some(Target::ArrayBuffer));
+        merged.attributes.insert(name, id);
+    }
+    merged.indices = Some(builder.indices(&format!("{}_indices", prefix), &indices));
+    Some(merged)
+}
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_merges_primitives_sharing_a_material() {
+        let mut gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "a": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 3, "type": "VEC3"
+                },
+                "b": {
+                    "bufferView": "view", "byteOffset": 36,
+                    "componentType": 5126, "count": 3, "type": "VEC3"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteLength": 72 }
+            },
+            "meshes": {
+                "mesh": {
+                    "primitives": [
+                        { "attributes": { "POSITION": "a" }, "material": "red" },
+                        { "attributes": { "POSITION": "a" }, "material": "blue" },
+                        { "attributes": { "POSITION": "b" }, "material": "red" }
+                    ]
+                }
+            },
+            "nodes": {
+                "node": { "meshes": ["mesh"] }
+            },
+            "scenes": {
+                "scene": { "nodes": ["node"] }
+            }
+        }"#).unwrap();
+        let data: Vec<f32> = (0..18).map(|i| i as f32).collect();
+        let mut buffers = Buffers::new();
+        buffers.insert("buffer".to_string(),
+                       data.iter().flat_map(|x| x.to_le_bytes()).collect());
+        assert_eq!(vec![vec![0, 2]], batches(&gltf, &gltf.meshes["mesh"]));
+        let before = gltf.scenes["scene"].flatten(&gltf, &buffers);
+
+        assert_eq!(1, merge_primitives(&mut gltf, &mut buffers, "merged"));
+        let primitives = &gltf.meshes["mesh"].primitives;
+        assert_eq!(vec!["red", "blue"],
+                   primitives.iter().map(|p| p.material.as_str()).collect::<Vec<_>>());
+        let indices = primitives[0].read_indices(&gltf, &buffers);
+        assert_eq!(Some(vec![0, 1, 2, 3, 4, 5]), indices);
+        let after = gltf.scenes["scene"].flatten(&gltf, &buffers);
+        assert_eq!(before.positions.len(), after.positions.len());
+        assert_eq!(before.indices.len(), after.indices.len());
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Passes that rewrite an asset and its buffer data for cheaper rendering.
+//!
+//! New data is appended through a `builder::Builder`, so the original
+//! accessors stay intact for any object that still references them. Passes
+//! that only move data without changing its values patch accessors in place.
+
+mod atlas;
+mod bake;
+mod convert;
+mod interleave;
+mod merge;
+mod strip;
+mod vertex_cache;
+
+pub use self::atlas::{apply_atlas, Atlas, Region};
+pub use self::bake::bake_transforms;
+pub use self::convert::{conversion_matrix, convert_coordinates, flip_handedness, Axis, Up};
+pub use self::interleave::{deinterleave, interleave};
+pub use self::merge::{batches, merge_primitives};
+pub use self::strip::strip_attributes;
+pub use self::vertex_cache::{optimize_vertex_cache, tipsify, CACHE_SIZE};
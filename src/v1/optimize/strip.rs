@@ -0,0 +1,34 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use v1::mesh::{Primitive, Semantic};
+
+/// Removes every attribute of a primitive whose semantic is not in `keep`.
+///
+/// Only the references are removed; accessors that are no longer used by any
+/// primitive remain in the asset.
+pub fn strip_attributes(primitive: &mut Primitive, keep: &[Semantic]) {
+    primitive.attributes.retain(|name, _| keep.contains(&Semantic::from_attribute(name)));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_keeps_selected_semantics() {
+        let mut primitive = Primitive::default();
+        for name in &["POSITION", "NORMAL", "TEXCOORD_0", "TEXCOORD_1", "_BATCHID"] {
+            primitive.attributes.insert(name.to_string(), name.to_lowercase());
+        }
+        strip_attributes(&mut primitive, &[Semantic::Position, Semantic::TexCoord(1)]);
+        let mut names: Vec<_> = primitive.attributes.keys().cloned().collect();
+        names.sort();
+        assert_eq!(vec!["POSITION", "TEXCOORD_1"], names);
+    }
+}
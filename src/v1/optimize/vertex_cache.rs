@@ -0,0 +1,217 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::HashMap;
+use v1::Gltf;
+use v1::accessor::ComponentType;
+use v1::buffer::{Buffers, Target};
+use v1::builder::Builder;
+use v1::mesh::{self, Mode};
+
+/// The number of vertices assumed to fit in the post-transform vertex cache.
+pub const CACHE_SIZE: usize = 16;
+
+/// Reorders the triangles of every indexed triangle list to make better use
+/// of the post-transform vertex cache.
+///
+/// The reordered indices are written to new accessors appended to `buffer`,
+/// keeping their original component width. Primitives sharing an index
+/// accessor share the reordered accessor too. Primitives without a `POSITION`
+/// accessor are skipped, and triangles indexing past it are dropped. Returns
+/// the number of index accessors rewritten.
+pub fn optimize_vertex_cache(
+    gltf: &mut Gltf,
+    buffers: &mut Buffers,
+    buffer: &str,
+) -> usize {
+    let mut reordered = HashMap::new();
+    for mesh in gltf.meshes.values() {
+        for primitive in &mesh.primitives {
+            let id = match (primitive.mode, primitive.indices.as_ref()) {
+                (Mode::Triangles, Some(id)) if !reordered.contains_key(id) => id,
+                _ => continue,
+            };
+            let accessor = match gltf.accessors.get(id) {
+                Some(accessor) => accessor,
+                None => continue,
+            };
+            let indices: Vec<u32> = match accessor.view(gltf, buffers) {
+                Some(view) => view.iter().collect(),
+                None => continue,
+            };
+            let vertex_count = match primitive.vertex_count(gltf) {
+                Some(count) => count,
+                None => continue,
+            };
+            let indices = tipsify(&indices, vertex_count, CACHE_SIZE);
+            reordered.insert(id.clone(), (accessor.component_type, indices));
+        }
+    }
+
+    let count = reordered.len();
+    let mut builder = Builder::new(gltf, buffers, buffer);
+    let mut ids = HashMap::new();
+    for (id, (component_type, indices)) in reordered {
+        let new_id = format!("{}_reordered", id);
+        let target = Some(Target::ElementArrayBuffer);
+        let narrow = match component_type {
+            ComponentType::U32 => None,
+            _ => mesh::narrow_indices(&indices),
+        };
+        let new_id = match narrow {
+            Some(narrow) => builder.accessor(&new_id, &narrow, target),
+            None => builder.accessor(&new_id, &indices, target),
+        };
+        ids.insert(id, new_id);
+    }
+    for mesh in builder.gltf().meshes.values_mut() {
+        for primitive in &mut mesh.primitives {
+            if let Some(id) = primitive.indices.as_ref().and_then(|id| ids.get(id)) {
+                primitive.indices = Some(id.clone());
+            }
+        }
+    }
+    count
+}
+
+/// Reorders the triangles of an indexed triangle list with the Tipsify
+/// algorithm of Sander, Nehab, and Barczak.
+///
+/// `vertex_count` is the number of vertices; triangles with an index beyond
+/// it are dropped. Triangles keep their winding order.
+pub fn tipsify(indices: &[u32], vertex_count: usize, cache_size: usize) -> Vec<u32> {
+    let triangle_count = indices.len() / 3;
+
+    // The triangles using each vertex.
+    let mut adjacency = vec![Vec::new(); vertex_count];
+    for triangle in 0..triangle_count {
+        let vertices = &indices[triangle * 3..triangle * 3 + 3];
+        if vertices.iter().any(|&i| i as usize >= vertex_count) {
+            continue;
+        }
+        for &i in vertices {
+            adjacency[i as usize].push(triangle);
+        }
+    }
+    let mut live: Vec<usize> = adjacency.iter().map(Vec::len).collect();
+    let mut cache_time = vec![0; vertex_count];
+    let mut emitted = vec![false; triangle_count];
+    let mut dead_ends = Vec::new();
+    let mut time = cache_size + 1;
+    let mut cursor = 0;
+    let mut output = Vec::with_capacity(triangle_count * 3);
+
+    let mut fanning = if vertex_count > 0 { Some(0) } else { None };
+    while let Some(vertex) = fanning {
+        let mut candidates = Vec::new();
+        for &triangle in &adjacency[vertex] {
+            if emitted[triangle] {
+                continue;
+            }
+            emitted[triangle] = true;
+            for &i in &indices[triangle * 3..triangle * 3 + 3] {
+                let i = i as usize;
+                output.push(i as u32);
+                dead_ends.push(i);
+                candidates.push(i);
+                live[i] -= 1;
+                if time - cache_time[i] > cache_size {
+                    cache_time[i] = time;
+                    time += 1;
+                }
+            }
+        }
+
+        // Prefer the candidate that stays in the cache longest while it
+        // still has triangles to emit.
+        let mut best = None;
+        let mut best_priority = 0;
+        for &i in &candidates {
+            if live[i] == 0 {
+                continue;
+            }
+            let age = time - cache_time[i];
+            let priority = if age + 2 * live[i] <= cache_size { age } else { 0 };
+            if best.is_none() || priority > best_priority {
+                best = Some(i);
+                best_priority = priority;
+            }
+        }
+        fanning = best.or_else(|| {
+            while let Some(i) = dead_ends.pop() {
+                if live[i] > 0 {
+                    return Some(i);
+                }
+            }
+            while cursor < vertex_count {
+                if live[cursor] > 0 {
+                    return Some(cursor);
+                }
+                cursor += 1;
+            }
+            None
+        });
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Returns the number of cache misses of a FIFO cache.
+    fn misses(indices: &[u32], cache_size: usize) -> usize {
+        let mut cache = Vec::new();
+        let mut misses = 0;
+        for &i in indices {
+            if !cache.contains(&i) {
+                misses += 1;
+                cache.push(i);
+                if cache.len() > cache_size {
+                    cache.remove(0);
+                }
+            }
+        }
+        misses
+    }
+
+    #[test]
+    fn it_keeps_every_triangle() {
+        // A 16x16 grid of quads with its rows in reverse order.
+        let mut indices = Vec::new();
+        for y in (0..16).rev() {
+            for x in 0..16 {
+                let i = y * 17 + x;
+                indices.extend_from_slice(&[i, i + 1, i + 17, i + 1, i + 18, i + 17]);
+            }
+        }
+        let reordered = tipsify(&indices, 17 * 17, 8);
+
+        let mut before: Vec<_> = indices.chunks(3).map(rotate).collect();
+        let mut after: Vec<_> = reordered.chunks(3).map(rotate).collect();
+        before.sort();
+        after.sort();
+        assert_eq!(before, after);
+        assert!(misses(&reordered, 8) <= misses(&indices, 8));
+    }
+
+    #[test]
+    fn it_drops_out_of_range_triangles() {
+        let indices = [0, 1, 2, 2, 1, u32::MAX, 1, 3, 2];
+        let reordered = tipsify(&indices, 4, 8);
+        let mut after: Vec<_> = reordered.chunks(3).map(rotate).collect();
+        after.sort();
+        assert_eq!(vec![[0, 1, 2], [1, 3, 2]], after);
+    }
+
+    /// Rotates a triangle to start at its smallest index, preserving winding.
+    fn rotate(t: &[u32]) -> [u32; 3] {
+        let min = (0..3).min_by_key(|&k| t[k]).unwrap();
+        [t[min], t[(min + 1) % 3], t[(min + 2) % 3]]
+    }
+}
@@ -6,6 +6,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Program {
     /// Names of GLSL vertex shader attributes.
@@ -25,4 +28,22 @@ pub struct Program {
     /// This is not necessarily unique, e.g., a program and a buffer could have
     /// the same name, or two programs could even have the same name.
     pub name: Option<String>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
 }
+
+impl_references!(Program {
+    ids {
+        fragment_shader: "fragmentShader" => shaders,
+        vertex_shader: "vertexShader" => shaders,
+    }
+    nested {}
+});
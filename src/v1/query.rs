@@ -0,0 +1,190 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Spatial queries against the meshes of a scene.
+
+use v1::Gltf;
+use v1::buffer::Buffers;
+use v1::math;
+use v1::mesh::{self, Semantic};
+use v1::scene::Scene;
+
+/// A half-line in world space.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    /// The starting point of the ray.
+    pub origin: [f32; 3],
+
+    /// The direction of the ray, which need not be normalized.
+    pub direction: [f32; 3],
+}
+
+/// An intersection of a ray with a triangle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hit<'a> {
+    /// The ID of the node instancing the mesh.
+    pub node: &'a str,
+
+    /// The ID of the mesh.
+    pub mesh: &'a str,
+
+    /// The index of the primitive within the mesh.
+    pub primitive: usize,
+
+    /// The vertex indices of the triangle.
+    pub triangle: [u32; 3],
+
+    /// The world-space distance from the ray origin to the hit point.
+    pub distance: f32,
+
+    /// The world-space hit point.
+    pub point: [f32; 3],
+
+    /// The barycentric co-ordinates of the hit point, weighting the vertices
+    /// of `triangle` in order.
+    pub barycentric: [f32; 3],
+}
+
+/// Returns every intersection of a ray with the triangles of a scene, nearest
+/// first.
+///
+/// Node transforms are accumulated from the root nodes downward and triangles
+/// are hit from either side. Primitives whose data cannot be read from
+/// `buffers` are skipped.
+pub fn raycast<'a>(
+    gltf: &'a Gltf,
+    scene: &'a Scene,
+    buffers: &Buffers,
+    ray: &Ray,
+) -> Vec<Hit<'a>> {
+    let mut hits = Vec::new();
+    let mut reader = mesh::Reader::new(gltf, buffers);
+    for visit in scene.traverse(gltf) {
+        for id in &visit.node.meshes {
+            let mesh = match gltf.meshes.get(id) {
+                Some(mesh) => mesh,
+                None => continue,
+            };
+            for (index, primitive) in mesh.primitives.iter().enumerate() {
+                let positions = match reader.vectors(primitive, &Semantic::Position) {
+                    Some(positions) => positions,
+                    None => continue,
+                };
+                let indices = match reader.indices(primitive, positions.len()) {
+                    Some(indices) => indices,
+                    None => continue,
+                };
+                let positions: Vec<_> = positions.iter()
+                    .map(|&position| math::transform_point(&visit.transform, position))
+                    .collect();
+                for triangle in primitive.triangles(indices.iter().cloned()) {
+                    let corners = match (positions.get(triangle[0] as usize),
+                                         positions.get(triangle[1] as usize),
+                                         positions.get(triangle[2] as usize)) {
+                        (Some(&a), Some(&b), Some(&c)) => [a, b, c],
+                        _ => continue,
+                    };
+                    if let Some((t, u, v)) = intersect(ray, corners) {
+                        hits.push(Hit {
+                            node: visit.id,
+                            mesh: id,
+                            primitive: index,
+                            triangle,
+                            distance: t * math::length(ray.direction),
+                            point: math::add(ray.origin, math::scale(ray.direction, t)),
+                            barycentric: [1.0 - u - v, u, v],
+                        });
+                    }
+                }
+            }
+        }
+    }
+    hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    hits
+}
+
+/// Intersects a ray with a triangle using the Möller–Trumbore algorithm.
+///
+/// Returns the ray parameter and the barycentric co-ordinates of the second
+/// and third corners.
+fn intersect(ray: &Ray, [a, b, c]: [[f32; 3]; 3]) -> Option<(f32, f32, f32)> {
+    let e1 = math::sub(b, a);
+    let e2 = math::sub(c, a);
+    let p = math::cross(ray.direction, e2);
+    let det = math::dot(e1, p);
+    if det == 0.0 {
+        // The ray is parallel to the triangle.
+        return None;
+    }
+    let s = math::sub(ray.origin, a);
+    let u = math::dot(s, p) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = math::cross(s, e1);
+    let v = math::dot(ray.direction, q) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = math::dot(e2, q) / det;
+    if t < 0.0 {
+        return None;
+    }
+    Some((t, u, v))
+}
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_hits_transformed_triangles() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "positions": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 3, "type": "VEC3"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteLength": 36 }
+            },
+            "meshes": {
+                "triangle": {
+                    "primitives": [{
+                        "attributes": { "POSITION": "positions" },
+                        "material": "material"
+                    }]
+                }
+            },
+            "nodes": {
+                "near": { "meshes": ["triangle"], "translation": [0, 0, -2] },
+                "far": { "meshes": ["triangle"], "translation": [0, 0, -5] }
+            },
+            "scenes": {
+                "scene": { "nodes": ["far", "near"] }
+            }
+        }"#).unwrap();
+        let positions: [f32; 9] = [-1.0, -1.0, 0.0, 1.0, -1.0, 0.0, 0.0, 1.0, 0.0];
+        let mut buffers = Buffers::new();
+        buffers.insert("buffer".to_string(),
+                       positions.iter().flat_map(|x| x.to_le_bytes()).collect());
+
+        let ray = Ray { origin: [0.0, 0.0, 0.0], direction: [0.0, 0.0, -2.0] };
+        let hits = raycast(&gltf, &gltf.scenes["scene"], &buffers, &ray);
+        assert_eq!(vec!["near", "far"],
+                   hits.iter().map(|hit| hit.node).collect::<Vec<_>>());
+        assert_eq!(2.0, hits[0].distance);
+        assert_eq!([0.0, 0.0, -2.0], hits[0].point);
+        assert_eq!([0.25, 0.25, 0.5], hits[0].barycentric);
+
+        let ray = Ray { origin: [5.0, 0.0, 0.0], direction: [0.0, 0.0, -1.0] };
+        assert!(raycast(&gltf, &gltf.scenes["scene"], &buffers, &ray).is_empty());
+    }
+}
@@ -6,10 +6,12 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use v1::extensions::Extensions;
+use v1::extras::Extras;
 use v1::texture::Filter;
 use v1::texture::Wrap;
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Sampler {
     /// Magnification filter.
     #[serde(rename = "magFilter")]
@@ -32,6 +34,32 @@ pub struct Sampler {
     pub wrap_t: Wrap,
 
     pub name: Option<String>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+impl Default for Sampler {
+    /// Returns the sampler described by the specification's default values,
+    /// used when a texture references no sampler.
+    fn default() -> Self {
+        Sampler {
+            mag_filter: sample_mag_filter_default(),
+            min_filter: sample_min_filter_default(),
+            wrap_s: sample_wrap_s_default(),
+            wrap_t: sample_wrap_t_default(),
+            name: None,
+            extensions: Extensions::default(),
+            extras: Extras::default(),
+        }
+    }
 }
 
 fn sample_mag_filter_default() -> Filter {
@@ -6,6 +6,16 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use v1::Gltf;
+use v1::buffer::Buffers;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+use v1::math;
+use v1::mesh::{self, BoundingBox, Mode, Primitive, Semantic};
+use v1::node::Node;
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Scene {
     /// The IDs of each root node.
@@ -17,4 +27,417 @@ pub struct Scene {
     /// This is not necessarily unique, e.g., a scene and a buffer could have
     /// the same name, or two scenes could even have the same name.
     pub name: Option<String>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+impl Scene {
+    /// Returns the world-space bounds of every mesh instanced by this scene.
+    ///
+    /// Node transforms are accumulated from the root nodes downward, and each
+    /// mesh's bounds are transformed into world space. `None` is returned when
+    /// no mesh in the scene has known bounds.
+    pub fn bounding_box(&self, gltf: &Gltf) -> Option<BoundingBox> {
+        let mut bounds = None;
+        for visit in self.traverse(gltf) {
+            for mesh in visit.node.meshes.iter().filter_map(|id| gltf.meshes.get(id)) {
+                if let Some(mesh_bounds) = mesh.bounding_box(gltf) {
+                    let world_bounds = mesh_bounds.transform(&visit.transform);
+                    bounds = Some(match bounds {
+                        Some(ref acc) => BoundingBox::union(acc, &world_bounds),
+                        None => world_bounds,
+                    });
+                }
+            }
+        }
+        bounds
+    }
+
+    /// Returns the world transform of every node in this scene, keyed by node
+    /// ID.
+    ///
+    /// Local transforms are accumulated from the root nodes downward. Each
+    /// matrix is stored in column-major order.
+    pub fn world_transforms(&self, gltf: &Gltf) -> HashMap<String, [f32; 16]> {
+        self.traverse(gltf)
+            .map(|visit| (visit.id.to_string(), visit.transform))
+            .collect()
+    }
+
+    /// Bakes node transforms into the geometry of this scene and concatenates
+    /// every triangle primitive into a single indexed triangle list.
+    ///
+    /// Normals are transformed by the inverse transpose of each node's world
+    /// transform, or computed from the world-space triangles for primitives
+    /// without normals. Triangles instanced by mirroring transforms have their
+    /// winding reversed so that they stay counter-clockwise. Primitives whose
+    /// data cannot be read from `buffers` are skipped.
+    pub fn flatten(&self, gltf: &Gltf, buffers: &Buffers) -> Flattened {
+        let mut flattened = Flattened::default();
+        let mut reader = mesh::Reader::new(gltf, buffers);
+        for visit in self.traverse(gltf) {
+            let normal_matrix = math::normal_matrix(&visit.transform);
+            let mirrored = math::determinant3(&visit.transform) < 0.0;
+            for mesh in visit.node.meshes.iter().filter_map(|id| gltf.meshes.get(id)) {
+                for primitive in &mesh.primitives {
+                    flattened.append(&mut reader, primitive, &visit.transform,
+                                     normal_matrix.as_ref(), mirrored);
+                }
+            }
+        }
+        flattened
+    }
+
+    /// Visits every node in this scene in depth-first pre-order.
+    pub fn traverse<'a>(&'a self, gltf: &'a Gltf) -> Traverse<'a> {
+        Traverse::new(gltf, &self.nodes, &math::IDENTITY, Order::DepthFirst)
+    }
+
+    /// Visits every node in this scene in breadth-first order.
+    pub fn traverse_breadth_first<'a>(&'a self, gltf: &'a Gltf) -> Traverse<'a> {
+        Traverse::new(gltf, &self.nodes, &math::IDENTITY, Order::BreadthFirst)
+    }
+}
+
+/// The world-space geometry of a scene as a single indexed triangle list.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Flattened {
+    /// World-space vertex positions.
+    pub positions: Vec<[f32; 3]>,
+
+    /// World-space unit vertex normals.
+    pub normals: Vec<[f32; 3]>,
+
+    /// Three vertex indices per counter-clockwise triangle.
+    pub indices: Vec<u32>,
+}
+
+impl Flattened {
+    fn append(
+        &mut self,
+        reader: &mut mesh::Reader,
+        primitive: &Primitive,
+        transform: &[f32; 16],
+        normal_matrix: Option<&[f32; 16]>,
+        mirrored: bool,
+    ) {
+        match primitive.mode {
+            Mode::Triangles | Mode::TriangleStrip | Mode::TriangleFan => {},
+            Mode::Points | Mode::Lines | Mode::LineLoop | Mode::LineStrip => return,
+        }
+        let positions = match reader.vectors(primitive, &Semantic::Position) {
+            Some(positions) => positions,
+            None => return,
+        };
+        let indices = match reader.indices(primitive, positions.len()) {
+            Some(indices) => indices,
+            None => return,
+        };
+        let positions: Vec<_> = positions.iter()
+            .map(|&position| math::transform_point(transform, position))
+            .collect();
+        let count = positions.len() as u32;
+        let triangles: Vec<_> = primitive.triangles(indices.iter().cloned())
+            .filter(|triangle| triangle.iter().all(|&i| i < count))
+            .map(|[a, b, c]| if mirrored { [a, c, b] } else { [a, b, c] })
+            .collect();
+        let normals = match (reader.vectors(primitive, &Semantic::Normal), normal_matrix) {
+            (Some(ref normals), Some(matrix)) if normals.len() == positions.len() => {
+                normals.iter()
+                    .map(|&normal| math::transform_vector(matrix, normal))
+                    .map(math::normalize)
+                    .collect()
+            },
+            _ => mesh::compute_normals(&positions, triangles.iter().cloned()),
+        };
+
+        let base = self.positions.len() as u32;
+        self.positions.extend(positions);
+        self.normals.extend(normals);
+        for triangle in triangles {
+            self.indices.extend(triangle.iter().map(|&i| base + i));
+        }
+    }
+}
+
+/// A node reached during a scene traversal.
+#[derive(Clone, Debug)]
+pub struct Visit<'a> {
+    /// The ID of the node.
+    pub id: &'a str,
+
+    /// The node itself.
+    pub node: &'a Node,
+
+    /// The number of ancestors between the node and the traversal's roots.
+    pub depth: usize,
+
+    /// The node's transform accumulated from the traversal's roots, in
+    /// column-major order.
+    pub transform: [f32; 16],
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Order {
+    DepthFirst,
+    BreadthFirst,
+}
+
+/// An iterator over the nodes of a hierarchy, in depth-first pre-order or
+/// breadth-first order.
+///
+/// Node IDs that do not exist are skipped. Each node is visited at most once,
+/// so a node reached again through a cycle or a second parent, which a valid
+/// asset never has, is skipped as well.
+#[derive(Clone, Debug)]
+pub struct Traverse<'a> {
+    gltf: &'a Gltf,
+    order: Order,
+    pending: VecDeque<(&'a str, usize, [f32; 16])>,
+    visited: HashSet<&'a str>,
+}
+
+impl<'a> Traverse<'a> {
+    pub(crate) fn new(
+        gltf: &'a Gltf,
+        roots: &'a [String],
+        parent: &[f32; 16],
+        order: Order,
+    ) -> Self {
+        let mut traverse = Traverse {
+            gltf,
+            order,
+            pending: VecDeque::new(),
+            visited: HashSet::new(),
+        };
+        traverse.push(roots, 0, parent);
+        traverse
+    }
+
+    fn push(&mut self, ids: &'a [String], depth: usize, parent: &[f32; 16]) {
+        match self.order {
+            // Children are popped from the front in document order.
+            Order::DepthFirst => {
+                for id in ids.iter().rev() {
+                    self.pending.push_front((id, depth, *parent));
+                }
+            },
+            Order::BreadthFirst => {
+                for id in ids {
+                    self.pending.push_back((id, depth, *parent));
+                }
+            },
+        }
+    }
+}
+
+impl<'a> Iterator for Traverse<'a> {
+    type Item = Visit<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((id, depth, parent)) = self.pending.pop_front() {
+            let node = match self.gltf.nodes.get(id) {
+                Some(node) => node,
+                None => continue,
+            };
+            if !self.visited.insert(id) {
+                continue;
+            }
+            let transform = math::mul(&parent, &node.local_matrix());
+            self.push(&node.children, depth + 1, &transform);
+            return Some(Visit { id, node, depth, transform });
+        }
+        None
+    }
+}
+
+impl_references!(Scene {
+    ids { nodes: "nodes" => nodes, }
+    nested {}
+});
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use v1::Gltf;
+    use v1::buffer::Buffers;
+    use v1::math;
+    use v1::mesh::BoundingBox;
+    use v1::scene::Flattened;
+
+    #[test]
+    fn it_stops_at_cycles() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "nodes": {
+                "a": { "children": ["b"] },
+                "b": { "children": ["a", "b"] }
+            },
+            "scenes": {
+                "scene": { "nodes": ["a"] }
+            }
+        }"#).unwrap();
+        let ids: Vec<_> = gltf.scenes["scene"].traverse(&gltf)
+            .map(|visit| visit.id)
+            .collect();
+        assert_eq!(vec!["a", "b"], ids);
+    }
+
+    #[test]
+    fn it_flattens_to_world_space() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "positions": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 3, "type": "VEC3"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteLength": 36 }
+            },
+            "meshes": {
+                "triangle": {
+                    "primitives": [{
+                        "attributes": { "POSITION": "positions" },
+                        "material": "material"
+                    }]
+                }
+            },
+            "nodes": {
+                "moved": { "meshes": ["triangle"], "translation": [0, 0, 1] },
+                "mirrored": { "meshes": ["triangle"], "scale": [-1, 1, 1] }
+            },
+            "scenes": {
+                "scene": { "nodes": ["moved", "mirrored"] }
+            }
+        }"#).unwrap();
+        let positions: [f32; 9] = [-1.0, -1.0, 0.0, 1.0, -1.0, 0.0, 0.0, 1.0, 0.0];
+        let mut buffers = Buffers::new();
+        buffers.insert("buffer".to_string(),
+                       positions.iter().flat_map(|x| x.to_le_bytes()).collect());
+
+        let flattened = gltf.scenes["scene"].flatten(&gltf, &buffers);
+        assert_eq!(Flattened {
+            positions: vec![
+                [-1.0, -1.0, 1.0], [1.0, -1.0, 1.0], [0.0, 1.0, 1.0],
+                [1.0, -1.0, 0.0], [-1.0, -1.0, 0.0], [0.0, 1.0, 0.0],
+            ],
+            normals: vec![[0.0, 0.0, 1.0]; 6],
+            indices: vec![0, 1, 2, 3, 5, 4],
+        }, flattened);
+    }
+
+    #[test]
+    fn it_computes_world_space_bounds() {
+        let data = r#"{
+    "accessors": {
+        "positions": {
+            "bufferView": "view",
+            "byteOffset": 0,
+            "componentType": 5126,
+            "count": 8,
+            "type": "VEC3",
+            "min": [-1.0, -1.0, -1.0],
+            "max": [1.0, 1.0, 1.0]
+        }
+    },
+    "meshes": {
+        "cube": {
+            "primitives": [
+                { "attributes": { "POSITION": "positions" }, "material": "m" }
+            ]
+        }
+    },
+    "nodes": {
+        "root": {
+            "children": ["child"],
+            "translation": [10.0, 0.0, 0.0]
+        },
+        "child": {
+            "meshes": ["cube"],
+            "rotation": [0.0, 0.0, 0.7071068, 0.7071068],
+            "scale": [2.0, 1.0, 1.0]
+        }
+    },
+    "scenes": {
+        "scene": { "nodes": ["root"] }
+    }
+}"#;
+        let gltf: Gltf = serde_json::from_str(data).unwrap();
+        let bounds = gltf.scenes["scene"].bounding_box(&gltf).unwrap();
+        let expected = BoundingBox {
+            min: [9.0, -2.0, -1.0],
+            max: [11.0, 2.0, 1.0],
+        };
+        for i in 0..3 {
+            assert!((bounds.min[i] - expected.min[i]).abs() < 1e-5);
+            assert!((bounds.max[i] - expected.max[i]).abs() < 1e-5);
+        }
+
+        let transforms = gltf.scenes["scene"].world_transforms(&gltf);
+        assert_eq!(2, transforms.len());
+        let child = transforms["child"];
+        assert!((child[12] - 10.0).abs() < 1e-5);
+        assert!((child[1] - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn it_traverses_depth_and_breadth_first() {
+        let data = r#"{
+    "nodes": {
+        "a": { "children": ["b", "c"], "translation": [1.0, 0.0, 0.0] },
+        "b": { "children": ["d"] },
+        "c": {},
+        "d": { "translation": [0.0, 2.0, 0.0] }
+    },
+    "scenes": {
+        "scene": { "nodes": ["a"] }
+    }
+}"#;
+        let gltf: Gltf = serde_json::from_str(data).unwrap();
+        let scene = &gltf.scenes["scene"];
+        let depth_first: Vec<(&str, usize)> = scene.traverse(&gltf)
+            .map(|visit| (visit.id, visit.depth))
+            .collect();
+        assert_eq!(vec![("a", 0), ("b", 1), ("d", 2), ("c", 1)], depth_first);
+        let breadth_first: Vec<&str> = scene.traverse_breadth_first(&gltf)
+            .map(|visit| visit.id)
+            .collect();
+        assert_eq!(vec!["a", "b", "c", "d"], breadth_first);
+
+        let d = scene.traverse(&gltf).find(|visit| visit.id == "d").unwrap();
+        assert_eq!([1.0, 2.0, 0.0], d.transform[12..15]);
+        let below_b: Vec<&str> = gltf.nodes["b"].descendants(&gltf, &math::IDENTITY)
+            .map(|visit| visit.id)
+            .collect();
+        assert_eq!(vec!["d"], below_b);
+    }
+
+    #[test]
+    fn it_finds_the_default_scene() {
+        let named: Gltf = serde_json::from_str(r#"{
+            "scene": "b",
+            "scenes": { "a": { "name": "A" }, "b": { "name": "B" } }
+        }"#).unwrap();
+        let name = named.default_scene().and_then(|scene| scene.name.as_deref());
+        assert_eq!(Some("B"), name);
+
+        let single: Gltf = serde_json::from_str(r#"{
+            "scenes": { "a": { "name": "A" } }
+        }"#).unwrap();
+        assert!(single.default_scene().is_some());
+
+        let ambiguous: Gltf = serde_json::from_str(r#"{
+            "scenes": { "a": {}, "b": {} }
+        }"#).unwrap();
+        assert!(ambiguous.default_scene().is_none());
+        assert!(Gltf::default().default_scene().is_none());
+    }
 }
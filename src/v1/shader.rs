@@ -6,6 +6,10 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use v1::binary;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+
 enum_number! {
     ShaderType {
         Fragment = 35632,
@@ -39,4 +43,22 @@ pub struct Shader {
     /// This is not necessarily unique, e.g., a shader and a buffer could have
     /// the same name, or two shaders could even have the same name.
     pub name: Option<String>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+impl Shader {
+    /// Returns the `KHR_binary_glTF` extension object, if the shader source is
+    /// embedded in a binary glTF body.
+    pub fn binary(&self) -> Option<binary::Shader> {
+        binary::extension(&self.extensions)
+    }
 }
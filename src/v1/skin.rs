@@ -6,6 +6,19 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::collections::HashMap;
+use std::vec;
+
+use v1::Gltf;
+use v1::accessor::{ComponentType, Kind};
+use v1::buffer::Buffers;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+use v1::math;
+use v1::node::{Hierarchy, Node};
+use v1::scene::{Order, Traverse};
+use v1::validation::{report, Code, Error, Pointer, Validate};
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Skin {
     #[serde(default = "skin_bind_shape_matrix")]
@@ -26,15 +39,374 @@ pub struct Skin {
     /// instance node's skeletons array.
     #[serde(default)]
     #[serde(rename = "jointNames")]
-    pub join_names: Vec<String>,
+    pub joint_names: Vec<String>,
 
     /// The user-defined name of this object.
     ///
     /// This is not necessarily unique, e.g., a skin and a buffer could have the
     /// same name, or two skins could even have the same name.
     pub name: Option<String>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+/// A joint of a skin, placed in the skeleton tree formed by the joints.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Joint<'a> {
+    /// The joint name, as listed in `Skin::joint_names`.
+    pub name: &'a str,
+
+    /// The ID of the node acting as this joint.
+    pub node: &'a str,
+
+    /// The index of the nearest ancestor node that is also a joint of the
+    /// skin, or `None` for a root of the skeleton.
+    pub parent: Option<usize>,
+
+    /// The world transform of the joint when the skin was bound, which is the
+    /// inverse of its inverse-bind matrix, in column-major order.
+    pub bind_matrix: [f32; 16],
+
+    /// The local transform of the joint node, in column-major order.
+    pub local_matrix: [f32; 16],
+}
+
+/// An `Iterator` over the joints of a skin and their inverse-bind matrices.
+#[derive(Clone, Debug)]
+pub struct IterJoints<'a> {
+    gltf: &'a Gltf,
+    nodes: vec::IntoIter<&'a str>,
+    inverse_bind_matrices: vec::IntoIter<[f32; 16]>,
+}
+
+impl<'a> Iterator for IterJoints<'a> {
+    /// The ID of the joint node, the node, and its inverse-bind matrix.
+    type Item = (&'a str, &'a Node, [f32; 16]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.nodes.next()?;
+        let inverse_bind_matrix = self.inverse_bind_matrices.next()
+            .unwrap_or(math::IDENTITY);
+        Some((id, &self.gltf.nodes[id], inverse_bind_matrix))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.nodes.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for IterJoints<'a> {}
+
+impl Skin {
+    /// Returns the node acting as the joint with the given index.
+    ///
+    /// The values of a skinned primitive's `JOINT` attribute index
+    /// `joint_names`, not nodes; this resolves such an index to the node with
+    /// the matching `jointName`, searching the subtrees of `skeletons` as
+    /// `joint_hierarchy` does. Returns the ID of the node and the node itself.
+    pub fn resolve_joint<'a>(
+        &self,
+        gltf: &'a Gltf,
+        skeletons: &'a [String],
+        index: usize,
+    ) -> Option<(&'a str, &'a Node)> {
+        let name = self.joint_names.get(index)?;
+        let is_joint = |node: &Node| node.joint_name.as_ref() == Some(name);
+        if skeletons.is_empty() {
+            gltf.nodes.iter()
+                .filter(|&(_, node)| is_joint(node))
+                .map(|(id, node)| (id.as_str(), node))
+                .min_by_key(|&(id, _)| id)
+        } else {
+            Traverse::new(gltf, skeletons, &math::IDENTITY, Order::DepthFirst)
+                .find(|visit| is_joint(visit.node))
+                .map(|visit| (visit.id, visit.node))
+        }
+    }
+
+    /// Returns an iterator over the joint nodes of this skin paired with their
+    /// inverse-bind matrices, in the order of `joint_names`.
+    ///
+    /// Joint nodes are found as by `joint_hierarchy`. Returns `None` if a
+    /// joint node cannot be found or the inverse-bind matrices cannot be read.
+    pub fn iter_joints<'a>(
+        &self,
+        gltf: &'a Gltf,
+        buffers: &Buffers,
+        skeletons: &'a [String],
+    ) -> Option<IterJoints<'a>> {
+        Some(IterJoints {
+            gltf,
+            nodes: self.joint_nodes(gltf, skeletons)?.into_iter(),
+            inverse_bind_matrices: self.read_inverse_bind_matrices(gltf, buffers)?
+                .into_iter(),
+        })
+    }
+
+    /// Returns the joints of this skin arranged as a tree, in the order of
+    /// `joint_names`, so that joint indices of skinned vertices index the
+    /// result.
+    ///
+    /// `skeletons` are the skeleton roots of the node instancing the skin. The
+    /// joint nodes are searched for in their subtrees or, when there are
+    /// none, among every node. Returns `None` if a joint node cannot be found
+    /// or the inverse-bind matrices cannot be read.
+    pub fn joint_hierarchy<'a>(
+        &'a self,
+        gltf: &'a Gltf,
+        buffers: &Buffers,
+        skeletons: &'a [String],
+    ) -> Option<Vec<Joint<'a>>> {
+        let nodes = self.joint_nodes(gltf, skeletons)?;
+        let inverse_bind_matrices = self.read_inverse_bind_matrices(gltf, buffers)?;
+        let indices: HashMap<&str, usize> = nodes.iter()
+            .enumerate()
+            .map(|(index, &node)| (node, index))
+            .collect();
+        let hierarchy = Hierarchy::new(gltf);
+        let joints = self.joint_names.iter()
+            .zip(nodes)
+            .enumerate()
+            .map(|(index, (name, node))| {
+                let inverse_bind_matrix = inverse_bind_matrices.get(index)
+                    .unwrap_or(&math::IDENTITY);
+                Joint {
+                    name,
+                    node,
+                    parent: hierarchy.ancestors(node)
+                        .find_map(|ancestor| indices.get(ancestor).cloned()),
+                    bind_matrix: math::invert(inverse_bind_matrix)
+                        .unwrap_or(math::IDENTITY),
+                    local_matrix: gltf.nodes[node].local_matrix(),
+                }
+            })
+            .collect();
+        Some(joints)
+    }
+
+    /// Finds the node acting as each joint, in the order of `joint_names`.
+    fn joint_nodes<'a>(
+        &self,
+        gltf: &'a Gltf,
+        skeletons: &'a [String],
+    ) -> Option<Vec<&'a str>> {
+        let mut nodes: HashMap<&str, &str> = HashMap::new();
+        let mut insert = |id: &'a str, name: Option<&'a str>| {
+            if let Some(name) = name {
+                // Keep the first match, or the smallest ID when scanning
+                // every node in arbitrary order.
+                let entry = nodes.entry(name).or_insert(id);
+                if skeletons.is_empty() && id < *entry {
+                    *entry = id;
+                }
+            }
+        };
+        if skeletons.is_empty() {
+            for (id, node) in &gltf.nodes {
+                insert(id, node.joint_name.as_deref());
+            }
+        } else {
+            let order = Order::DepthFirst;
+            for visit in Traverse::new(gltf, skeletons, &math::IDENTITY, order) {
+                insert(visit.id, visit.node.joint_name.as_deref());
+            }
+        }
+        self.joint_names.iter()
+            .map(|name| nodes.get(name.as_str()).cloned())
+            .collect()
+    }
+    /// Reads the inverse-bind matrices of this skin, one per joint.
+    ///
+    /// When the skin has no inverse-bind matrices, identity matrices are
+    /// returned. Returns `None` if the accessor data cannot be read.
+    pub fn read_inverse_bind_matrices(
+        &self,
+        gltf: &Gltf,
+        buffers: &Buffers,
+    ) -> Option<Vec<[f32; 16]>> {
+        match self.inverse_bind_matrices {
+            Some(ref id) => {
+                let view = gltf.accessors.get(id)?.view(gltf, buffers)?;
+                Some(view.iter().collect())
+            },
+            None => Some(vec![math::IDENTITY; self.joint_names.len()]),
+        }
+    }
+
+    /// Computes the matrix of each joint for use in vertex skinning.
+    ///
+    /// `inverse_bind_matrices` and `joint_transforms` give the inverse-bind
+    /// matrix and the world transform of each joint, in the order of
+    /// `joint_names`. `node_transform` is the world transform of the node
+    /// instancing the skinned mesh. Each joint matrix is
+    /// `inverse(node_transform) * joint_transform * inverse_bind_matrix *
+    /// bind_shape_matrix`, in column-major order.
+    pub fn joint_matrices(
+        &self,
+        inverse_bind_matrices: &[[f32; 16]],
+        joint_transforms: &[[f32; 16]],
+        node_transform: &[f32; 16],
+    ) -> Vec<[f32; 16]> {
+        let inverse_node = math::invert(node_transform).unwrap_or(math::IDENTITY);
+        joint_transforms
+            .iter()
+            .zip(inverse_bind_matrices)
+            .map(|(joint, ibm)| {
+                let bind = math::mul(ibm, &self.bind_shape_matrix);
+                math::mul(&inverse_node, &math::mul(joint, &bind))
+            })
+            .collect()
+    }
+}
+
+impl Validate for Skin {
+    fn validate(&self, gltf: &Gltf, pointer: &Pointer, errors: &mut Vec<Error>) {
+        let id = match self.inverse_bind_matrices {
+            Some(ref id) => id,
+            None => return,
+        };
+        let pointer = pointer.field("inverseBindMatrices");
+        let accessor = match gltf.accessors.get(id) {
+            Some(accessor) => accessor,
+            None => return,
+        };
+        let float = accessor.component_type == ComponentType::F32;
+        if accessor.kind != Kind::Mat4 || !float {
+            report(errors, Code::InvalidAccessorType, pointer.clone(),
+                   format!("inverse-bind matrices must be float MAT4, found {:?} {:?}",
+                           accessor.component_type, accessor.kind));
+        }
+        if accessor.count as usize != self.joint_names.len() {
+            report(errors, Code::CountMismatch, pointer,
+                   format!("skin has {} joints but {} inverse-bind matrices",
+                           self.joint_names.len(), accessor.count));
+        }
+    }
 }
 
 fn skin_bind_shape_matrix() -> [f32; 16] {
     [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]
 }
+
+impl_references!(Skin {
+    ids { inverse_bind_matrices: "inverseBindMatrices" => accessors, }
+    nested {}
+});
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_computes_joint_matrices() {
+        let skin = Skin {
+            bind_shape_matrix: math::IDENTITY,
+            joint_names: vec!["a".to_string(), "b".to_string()],
+            ..Default::default()
+        };
+        let translate = |x: f32| {
+            math::from_trs([x, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0], [1.0; 3])
+        };
+        // Joint b is bound one unit along x and has since moved to x = 3.
+        let ibms = [math::IDENTITY, translate(-1.0)];
+        let joints = [translate(2.0), translate(5.0)];
+        let matrices = skin.joint_matrices(&ibms, &joints, &translate(2.0));
+        assert_eq!(math::IDENTITY, matrices[0]);
+        assert_eq!(translate(2.0), matrices[1]);
+    }
+
+    #[test]
+    fn it_arranges_joints_as_a_tree() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "nodes": {
+                "root": { "children": ["hip"] },
+                "hip": {
+                    "jointName": "hip",
+                    "children": ["bone"],
+                    "translation": [0, 1, 0]
+                },
+                "bone": { "children": ["knee"] },
+                "knee": { "jointName": "knee" },
+                "other": { "jointName": "knee" }
+            },
+            "skins": {
+                "skin": { "jointNames": ["knee", "hip"] }
+            }
+        }"#).unwrap();
+        let skin = &gltf.skins["skin"];
+        let skeletons = vec!["root".to_string()];
+        let joints = skin.joint_hierarchy(&gltf, &Buffers::new(), &skeletons).unwrap();
+        assert_eq!(vec![("knee", "knee", Some(1)), ("hip", "hip", None)],
+                   joints.iter().map(|j| (j.name, j.node, j.parent)).collect::<Vec<_>>());
+        assert_eq!(math::IDENTITY, joints[0].bind_matrix);
+        assert_eq!(1.0, joints[1].local_matrix[13]);
+
+        let missing = vec!["bone".to_string()];
+        assert!(skin.joint_hierarchy(&gltf, &Buffers::new(), &missing).is_none());
+    }
+
+    #[test]
+    fn it_resolves_joint_indices_to_nodes() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "nodes": {
+                "root": { "children": ["b"] },
+                "a": { "jointName": "knee" },
+                "b": { "jointName": "knee" }
+            },
+            "skins": {
+                "skin": { "jointNames": ["knee"] }
+            }
+        }"#).unwrap();
+        let skin = &gltf.skins["skin"];
+        let skeletons = vec!["root".to_string()];
+        assert_eq!(Some("b"), skin.resolve_joint(&gltf, &skeletons, 0).map(|j| j.0));
+        assert_eq!(Some("a"), skin.resolve_joint(&gltf, &[], 0).map(|j| j.0));
+        assert!(skin.resolve_joint(&gltf, &skeletons, 1).is_none());
+
+        let joints: Vec<_> = skin.iter_joints(&gltf, &Buffers::new(), &skeletons)
+            .unwrap()
+            .map(|(id, _, ibm)| (id, ibm))
+            .collect();
+        assert_eq!(vec![("b", math::IDENTITY)], joints);
+    }
+
+    #[test]
+    fn it_validates_joint_count() {
+        let data = r#"{
+    "accessors": {
+        "ibm": {
+            "bufferView": "view",
+            "byteOffset": 0,
+            "componentType": 5126,
+            "count": 1,
+            "type": "MAT4"
+        }
+    },
+    "skins": {
+        "ok": { "inverseBindMatrices": "ibm", "jointNames": ["a"] },
+        "short": { "inverseBindMatrices": "ibm", "jointNames": ["a", "b"] },
+        "missing": { "inverseBindMatrices": "nope", "jointNames": ["a"] }
+    }
+}"#;
+        let gltf: Gltf = serde_json::from_str(data).unwrap();
+        let mut pointers: Vec<String> = gltf.validate()
+            .into_iter()
+            .map(|error| error.pointer.to_string())
+            .filter(|pointer| pointer.starts_with("/skins/"))
+            .collect();
+        pointers.sort();
+        assert_eq!(vec![
+            "/skins/missing/inverseBindMatrices",
+            "/skins/short/inverseBindMatrices",
+        ], pointers);
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Summary figures of an asset, for budgeting and reporting.
+
+use v1::Gltf;
+#[cfg(feature = "image")]
+use v1::image::Images;
+use v1::mesh::Primitive;
+use v1::scene::Scene;
+
+/// Counts and byte totals of an asset.
+///
+/// Draw calls, triangles, and vertices count every instance of a mesh, so a
+/// mesh referenced by two nodes is counted twice.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    /// The number of node instances in the scenes.
+    pub nodes: usize,
+
+    /// The number of primitive instances, each drawn with one draw call.
+    pub draw_calls: usize,
+
+    /// The number of triangles drawn, after converting strips and fans.
+    pub triangles: usize,
+
+    /// The number of vertices drawn, i.e. the `POSITION` counts.
+    pub vertices: usize,
+
+    /// The largest number of ancestors of any node.
+    pub max_depth: usize,
+
+    /// The total `byteLength` of all buffers.
+    pub buffer_bytes: usize,
+}
+
+impl Stats {
+    /// Collects the statistics of the default scene, or of every scene if
+    /// the asset has no default scene.
+    pub fn new(gltf: &Gltf) -> Stats {
+        let mut stats = Stats {
+            buffer_bytes: gltf.buffers.values().map(|buffer| buffer.byte_length).sum(),
+            ..Stats::default()
+        };
+        match gltf.default_scene() {
+            Some(scene) => stats.add_scene(gltf, scene),
+            None => {
+                for scene in gltf.scenes.values() {
+                    stats.add_scene(gltf, scene);
+                }
+            },
+        }
+        stats
+    }
+
+    fn add_scene(&mut self, gltf: &Gltf, scene: &Scene) {
+        for visit in scene.traverse(gltf) {
+            self.nodes += 1;
+            self.max_depth = self.max_depth.max(visit.depth);
+            for mesh in visit.node.meshes.iter().filter_map(|id| gltf.meshes.get(id)) {
+                for primitive in &mesh.primitives {
+                    self.add_primitive(gltf, primitive);
+                }
+            }
+        }
+    }
+
+    fn add_primitive(&mut self, gltf: &Gltf, primitive: &Primitive) {
+        self.draw_calls += 1;
+        self.vertices += primitive.vertex_count(gltf).unwrap_or(0);
+        self.triangles += primitive.triangle_count(gltf).unwrap_or(0);
+    }
+}
+
+/// Estimates the GPU memory needed by the images as uncompressed RGBA8
+/// textures with full mipmap chains.
+///
+/// Only image headers are read. Images whose dimensions cannot be read are
+/// skipped.
+#[cfg(feature = "image")]
+pub fn texture_bytes(images: &Images) -> u64 {
+    images.values()
+        .filter_map(|image| image.dimensions())
+        .map(|(width, height)| width as u64 * height as u64 * 4 * 4 / 3)
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_counts_instances() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "indices": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5123, "count": 6, "type": "SCALAR"
+                },
+                "positions": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 4, "type": "VEC3"
+                }
+            },
+            "buffers": {
+                "buffer": { "uri": "buffer.bin", "byteLength": 60 }
+            },
+            "meshes": {
+                "quad": {
+                    "primitives": [{
+                        "attributes": { "POSITION": "positions" },
+                        "indices": "indices",
+                        "material": "material"
+                    }]
+                }
+            },
+            "nodes": {
+                "root": { "children": ["child"], "meshes": ["quad"] },
+                "child": { "meshes": ["quad"] }
+            },
+            "scenes": {
+                "scene": { "nodes": ["root"] }
+            }
+        }"#).unwrap();
+        let stats = gltf.stats();
+        assert_eq!(Stats {
+            nodes: 2,
+            draw_calls: 2,
+            triangles: 4,
+            vertices: 8,
+            max_depth: 1,
+            buffer_bytes: 60,
+        }, stats);
+    }
+}
@@ -0,0 +1,275 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reading accessor data without loading whole buffers.
+
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::sync::Arc;
+use v1::{Error, Gltf};
+use v1::accessor::Element;
+use v1::import::{self, Source};
+use v1::validation::Pointer;
+
+/// The default size in bytes of the buffer ranges read at a time.
+pub const DEFAULT_PAGE_SIZE: usize = 64 * 1024;
+
+/// The default number of pages kept in memory.
+pub const DEFAULT_CAPACITY: usize = 64;
+
+/// Reads accessor data from a `Source` on demand, for assets whose buffers
+/// are too large to load with `import`.
+///
+/// Buffers with relative URIs are read in fixed-size pages, and only the pages
+/// spanning a requested accessor are read. The most recently used pages are
+/// kept in memory, up to a fixed number of them. Buffers with data URIs are
+/// decoded whole on first use, since they are already held in memory by the
+/// asset. The body of a binary glTF container is not available from a source,
+/// so this cannot read its `binary_glTF` buffer.
+#[derive(Debug)]
+pub struct Stream<'a, S: 'a> {
+    gltf: &'a Gltf,
+    source: &'a S,
+    page_size: usize,
+    capacity: usize,
+    pages: HashMap<(String, usize), Vec<u8>>,
+    /// Cached pages, from least to most recently used.
+    order: VecDeque<(String, usize)>,
+    decoded: HashMap<String, Arc<[u8]>>,
+}
+
+impl<'a, S: Source> Stream<'a, S> {
+    /// Creates a reader of the buffers of `gltf` from `source`, with the
+    /// default page size and capacity.
+    pub fn new(gltf: &'a Gltf, source: &'a S) -> Self {
+        Stream {
+            gltf,
+            source,
+            page_size: DEFAULT_PAGE_SIZE,
+            capacity: DEFAULT_CAPACITY,
+            pages: HashMap::new(),
+            order: VecDeque::new(),
+            decoded: HashMap::new(),
+        }
+    }
+
+    /// Sets the size in bytes of the buffer ranges read at a time, discarding
+    /// the pages already read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is zero.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        assert!(page_size > 0, "page size must not be zero");
+        self.page_size = page_size;
+        self.pages.clear();
+        self.order.clear();
+        self
+    }
+
+    /// Sets the number of pages kept in memory, which is at least one.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self.evict();
+        self
+    }
+
+    /// Reads the elements of the accessor with the given ID.
+    ///
+    /// Returns `Ok(None)` if the accessor, its buffer view, or its buffer is
+    /// missing, or if the accessor does not fit within its buffer view, and
+    /// an `Error::Load` naming the buffer if its data cannot be read.
+    pub fn read<T: Element>(&mut self, id: &str) -> Result<Option<Vec<T>>, Error> {
+        let gltf = self.gltf;
+        let accessor = match gltf.accessors.get(id) {
+            Some(accessor) => accessor,
+            None => return Ok(None),
+        };
+        let (buffer, range) = match accessor.location(gltf) {
+            Some(location) => location,
+            None => return Ok(None),
+        };
+        let data = match self.bytes(buffer, range.start, range.end) {
+            Ok(Some(data)) => data,
+            Ok(None) => return Ok(None),
+            Err(err) => {
+                let pointer = Pointer::root().field("buffers").field(buffer);
+                return Err(import::load_error(pointer, &gltf.buffers[buffer].uri, err));
+            },
+        };
+        Ok(Some(accessor.view_of(&data).iter().collect()))
+    }
+
+    /// Returns the given byte range of a buffer.
+    fn bytes(
+        &mut self,
+        buffer: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let gltf = self.gltf;
+        let length = match gltf.buffers.get(buffer) {
+            Some(buffer) if end <= buffer.byte_length => buffer.byte_length,
+            _ => return Ok(None),
+        };
+        let uri = &gltf.buffers[buffer].uri;
+        if uri.starts_with("data:") {
+            if !self.decoded.contains_key(buffer) {
+                let data = import::read_uri(self.source, uri)?;
+                if data.len() < length {
+                    return Err(Error::BufferLength(buffer.to_string()));
+                }
+                self.decoded.insert(buffer.to_string(), data);
+            }
+            return Ok(Some(self.decoded[buffer][start..end].to_vec()));
+        }
+
+        let mut data = Vec::with_capacity(end - start);
+        if start == end {
+            return Ok(Some(data));
+        }
+        for page in start / self.page_size..=(end - 1) / self.page_size {
+            let offset = page * self.page_size;
+            let bytes = self.page(buffer, page, length)?;
+            let from = start.max(offset) - offset;
+            let to = end.min(offset + bytes.len()) - offset;
+            data.extend_from_slice(&bytes[from..to]);
+        }
+        Ok(Some(data))
+    }
+
+    /// Returns a page of a buffer with a relative URI, reading it if needed.
+    fn page(&mut self, buffer: &str, page: usize, length: usize) -> Result<&[u8], Error> {
+        let key = (buffer.to_string(), page);
+        if self.pages.contains_key(&key) {
+            let position = self.order.iter().position(|k| *k == key).unwrap();
+            let key = self.order.remove(position).unwrap();
+            self.order.push_back(key);
+        } else {
+            let offset = page * self.page_size;
+            let len = self.page_size.min(length - offset);
+            let uri = &self.gltf.buffers[buffer].uri;
+            let data = self.source.read_range(uri, offset as u64, len)?;
+            if data.len() != len {
+                let message = format!("{}: read {} of {} bytes at offset {}",
+                                      uri, data.len(), len, offset);
+                let kind = io::ErrorKind::UnexpectedEof;
+                return Err(Error::Io(io::Error::new(kind, message)));
+            }
+            self.pages.insert(key.clone(), data);
+            self.order.push_back(key.clone());
+            self.evict();
+        }
+        Ok(&self.pages[&key])
+    }
+
+    /// Discards the least recently used pages beyond the capacity.
+    fn evict(&mut self) {
+        while self.order.len() > self.capacity {
+            let key = self.order.pop_front().unwrap();
+            self.pages.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_reads_accessors_in_pages() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "positions": {
+                    "bufferView": "view", "byteOffset": 4, "byteStride": 16,
+                    "componentType": 5126, "count": 3, "type": "VEC3"
+                },
+                "inline": {
+                    "bufferView": "inline_view", "byteOffset": 0,
+                    "componentType": 5123, "count": 2, "type": "SCALAR"
+                },
+                "outside": {
+                    "bufferView": "view", "byteOffset": 4,
+                    "componentType": 5126, "count": 100, "type": "VEC3"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "external", "byteOffset": 8, "byteLength": 52 },
+                "inline_view": { "buffer": "inline", "byteLength": 4 }
+            },
+            "buffers": {
+                "external": { "uri": "buffer.bin", "byteLength": 60 },
+                "inline": {
+                    "uri": "data:application/octet-stream;base64,AQACAA==",
+                    "byteLength": 4
+                }
+            }
+        }"#).unwrap();
+        let data: Vec<f32> = (0..15).map(|i| i as f32).collect();
+        let mut source = HashMap::new();
+        source.insert("buffer.bin".to_string(),
+                      data.iter().flat_map(|x| x.to_le_bytes()).collect::<Vec<u8>>());
+
+        let mut stream = Stream::new(&gltf, &source).page_size(7).capacity(2);
+        let positions: Vec<[f32; 3]> = stream.read("positions").unwrap().unwrap();
+        assert_eq!(vec![[3.0, 4.0, 5.0], [7.0, 8.0, 9.0], [11.0, 12.0, 13.0]], positions);
+        assert_eq!(2, stream.pages.len());
+        let inline: Vec<u32> = stream.read("inline").unwrap().unwrap();
+        assert_eq!(vec![1, 2], inline);
+        assert!(stream.read::<[f32; 3]>("outside").unwrap().is_none());
+        assert!(stream.read::<u32>("missing").unwrap().is_none());
+
+        let empty = HashMap::new();
+        match Stream::new(&gltf, &empty).read::<[f32; 3]>("positions") {
+            Err(Error::Load { ref pointer, ref uri, .. }) => {
+                assert_eq!("/buffers/external", pointer.as_str());
+                assert_eq!("buffer.bin", uri);
+            },
+            result => panic!("expected a missing file, found {:?}", result),
+        }
+    }
+
+    /// A source returning fewer bytes than requested.
+    struct Truncated;
+
+    impl Source for Truncated {
+        fn read(&self, _: &str) -> Result<Vec<u8>, Error> {
+            Ok(vec![0; 4])
+        }
+
+        fn read_range(&self, _: &str, _: u64, _: usize) -> Result<Vec<u8>, Error> {
+            self.read("")
+        }
+    }
+
+    #[test]
+    fn it_rejects_short_pages() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "values": {
+                    "bufferView": "view", "byteOffset": 0,
+                    "componentType": 5126, "count": 4, "type": "SCALAR"
+                }
+            },
+            "bufferViews": {
+                "view": { "buffer": "external", "byteLength": 16 }
+            },
+            "buffers": {
+                "external": { "uri": "buffer.bin", "byteLength": 16 }
+            }
+        }"#).unwrap();
+        match Stream::new(&gltf, &Truncated).read::<f32>("values") {
+            Err(Error::Load { ref cause, .. }) => match **cause {
+                Error::Io(ref err) => assert_eq!(io::ErrorKind::UnexpectedEof, err.kind()),
+                ref cause => panic!("expected an I/O error, found {:?}", cause),
+            },
+            result => panic!("expected a short read, found {:?}", result),
+        }
+    }
+}
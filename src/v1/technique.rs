@@ -6,7 +6,10 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use serde_json::Value;
 use std::collections::HashMap;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
 
 enum_number! {
     ParameterType {
@@ -89,6 +92,22 @@ pub struct Parameter {
     /// Attribute semantics can be of the form [semantic]_[set_index] for
     /// example "TEXCOORD_0".
     pub semantic: Option<String>,
+
+    /// The value of the parameter.
+    ///
+    /// A material value or a node transform takes precedence over this value.
+    /// For `SAMPLER_2D` parameters this is the ID of a texture.
+    pub value: Option<Value>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -182,12 +201,12 @@ pub struct Function {
     /// Floating-point value passed to lineWidth().
     #[serde(rename = "lineWidth")]
     #[serde(default = "function_line_width_default")]
-    pub line_width: [u32; 1],
+    pub line_width: [f32; 1],
 
     /// Floating-point value passed to polygonOffset(). [factor, units]
     #[serde(rename = "polygonOffset")]
     #[serde(default = "function_polygon_offset_default")]
-    pub polygon_offset: [u32; 2],
+    pub polygon_offset: [f32; 2],
 
     /// Floating-point value passed to scissor().
     ///
@@ -197,6 +216,16 @@ pub struct Function {
     /// created. width and height must be greater than zero.
     #[serde(default = "function_scissor_default")]
     pub scissor: [f32; 4],
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
 }
 
 fn function_blend_color_default() -> [f32; 4] {
@@ -235,12 +264,12 @@ fn function_front_face_default() -> [u32; 1] {
     [2305]
 }
 
-fn function_line_width_default() -> [u32; 1] {
-    [1]
+fn function_line_width_default() -> [f32; 1] {
+    [1.0]
 }
 
-fn function_polygon_offset_default() -> [u32; 2] {
-    [0, 0]
+fn function_polygon_offset_default() -> [f32; 2] {
+    [0.0, 0.0]
 }
 
 fn function_scissor_default() -> [f32; 4] {
@@ -261,11 +290,21 @@ pub struct State {
     /// 32926 (SAMPLE_ALPHA_TO_COVERAGE)
     /// 3089 (SCISSOR_TEST)
     #[serde(default)]
-    enable: Vec<u32>,
+    pub enable: Vec<u32>,
 
     /// Arguments for fixed-function rendering state functions other than
     /// enable() / disable().
-    functions: Option<Function>,
+    pub functions: Option<Function>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -275,28 +314,97 @@ pub struct Technique {
     /// Each parameter defines an attribute or uniform input, and an optional
     /// semantic and value.
     #[serde(default)]
-    parameters: HashMap<String, Parameter>,
+    pub parameters: HashMap<String, Parameter>,
 
     /// A dictionary object of strings that maps GLSL attribute names to
     /// technique parameter IDs.
     #[serde(default)]
-    attributes: HashMap<String, String>,
+    pub attributes: HashMap<String, String>,
 
     /// The ID of the program.
-    program: String,
+    pub program: String,
 
     /// A dictionary object of strings that maps GLSL uniform names to technique
     /// parameter IDs.
     #[serde(default)]
-    uniforms: HashMap<String, String>,
+    pub uniforms: HashMap<String, String>,
 
     /// Fixed-function rendering states.
     #[serde(default)]
-    states: Option<State>,
+    pub states: Option<State>,
 
     /// The user-defined name of this object.
     ///
     /// This is not necessarily unique, e.g., a technique and a buffer could
     /// have the same name, or two techniques could even have the same name.
-    name: Option<String>,
+    pub name: Option<String>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+impl Technique {
+    /// Returns the parameter bound to the given GLSL attribute.
+    pub fn attribute(&self, name: &str) -> Option<&Parameter> {
+        self.attributes.get(name).and_then(|id| self.parameters.get(id))
+    }
+
+    /// Returns the parameter bound to the given GLSL uniform.
+    pub fn uniform(&self, name: &str) -> Option<&Parameter> {
+        self.uniforms.get(name).and_then(|id| self.parameters.get(id))
+    }
+}
+
+impl_references!(Parameter {
+    ids { node: "node" => nodes, }
+    nested {}
+});
+
+impl_references!(Technique {
+    ids { program: "program" => programs, }
+    nested { parameters: "parameters", }
+});
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_deserializes_a_technique() {
+        let data = r#"{
+    "attributes": { "a_position": "position" },
+    "parameters": {
+        "diffuse": { "type": 35666, "value": [1.0, 0.0, 0.0, 1.0] },
+        "modelViewMatrix": { "semantic": "MODELVIEW", "type": 35676 },
+        "position": { "semantic": "POSITION", "type": 35665 }
+    },
+    "program": "program",
+    "states": {
+        "enable": [2884, 2929],
+        "functions": { "lineWidth": [1.5] }
+    },
+    "uniforms": {
+        "u_diffuse": "diffuse",
+        "u_modelViewMatrix": "modelViewMatrix"
+    }
+}"#;
+        let technique: Technique = serde_json::from_str(data).unwrap();
+        assert_eq!("program", technique.program);
+        let position = technique.attribute("a_position").unwrap();
+        assert_eq!(ParameterType::FloatVec3, position.kind);
+        assert_eq!(Some("POSITION"), position.semantic.as_deref());
+        let diffuse = technique.uniform("u_diffuse").unwrap();
+        assert_eq!(Some(Value::from(vec![1.0, 0.0, 0.0, 1.0])), diffuse.value);
+        let states = technique.states.unwrap();
+        assert_eq!(vec![2884, 2929], states.enable);
+        assert_eq!([1.5], states.functions.unwrap().line_width);
+    }
 }
@@ -6,6 +6,13 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::borrow::Cow;
+
+use v1::Gltf;
+use v1::extensions::Extensions;
+use v1::extras::Extras;
+use v1::sampler::Sampler;
+
 enum_number! {
     Filter {
         Nearest = 9728,
@@ -80,7 +87,25 @@ impl Default for TexelType {
     }
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+/// How the texel values of a texture are encoded.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColorSpace {
+    /// Color data stored with the sRGB transfer function, e.g. diffuse or
+    /// emissive maps.
+    Srgb,
+
+    /// Non-color data stored linearly, e.g. normal, metallic-roughness or
+    /// occlusion maps.
+    Linear,
+}
+
+/// Parameter name fragments that mark a texture as holding non-color data.
+const LINEAR_PARAMETERS: &[&str] = &[
+    "normal", "bump", "height", "displacement", "metal", "rough", "occlusion",
+    "shininess", "gloss",
+];
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Texture {
     /// The texture's format.
     #[serde(default)]
@@ -111,4 +136,117 @@ pub struct Texture {
     /// This is not necessarily unique, e.g., a texture and a buffer could have
     /// the same name, or two textures could even have the same name.
     pub name: Option<String>,
+
+    /// Dictionary object with extension-specific objects.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+
+    /// Application-specific data.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+impl Texture {
+    /// Returns the sampler used by this texture.
+    ///
+    /// Falls back to `Sampler::default()` when the referenced sampler does
+    /// not exist.
+    pub fn sampler<'a>(&self, gltf: &'a Gltf) -> Cow<'a, Sampler> {
+        match gltf.samplers.get(&self.sampler) {
+            Some(sampler) => Cow::Borrowed(sampler),
+            None => Cow::Owned(Sampler::default()),
+        }
+    }
+
+    /// Returns the color space the texture with the given ID should be
+    /// sampled in, judging by the material parameters that reference it.
+    ///
+    /// Textures bound to parameters such as `normalMap` or `occlusion` are
+    /// linear. Textures bound to color parameters such as `diffuse` or
+    /// `emission`, and textures no material references, are sRGB. A texture
+    /// used both ways is treated as sRGB.
+    pub fn color_space(id: &str, gltf: &Gltf) -> ColorSpace {
+        let mut linear = false;
+        for material in gltf.materials.values() {
+            for (parameter, texture) in material.textures(gltf) {
+                if texture != id {
+                    continue;
+                }
+                let parameter = parameter.to_lowercase();
+                if LINEAR_PARAMETERS.iter().any(|name| parameter.contains(name)) {
+                    linear = true;
+                } else {
+                    return ColorSpace::Srgb;
+                }
+            }
+        }
+        if linear {
+            ColorSpace::Linear
+        } else {
+            ColorSpace::Srgb
+        }
+    }
+}
+
+impl_references!(Texture {
+    ids {
+        sampler: "sampler" => samplers,
+        source: "source" => images,
+    }
+    nested {}
+});
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_falls_back_to_the_default_sampler() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "samplers": {
+                "clamp": { "wrapS": 33071, "wrapT": 33071 }
+            },
+            "textures": {
+                "a": { "sampler": "clamp", "source": "image" },
+                "b": { "sampler": "missing", "source": "image" }
+            }
+        }"#).unwrap();
+        let clamp = gltf.textures["a"].sampler(&gltf);
+        assert_eq!(Wrap::ClampToEdge, clamp.wrap_s);
+        assert_eq!(Filter::Linear, clamp.mag_filter);
+        let fallback = gltf.textures["b"].sampler(&gltf);
+        assert_eq!(Filter::NearestMipmapLinear, fallback.min_filter);
+        assert_eq!(Wrap::Repeat, fallback.wrap_t);
+    }
+
+    #[test]
+    fn it_tags_textures_by_material_usage() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "materials": {
+                "brick": {
+                    "values": {
+                        "diffuse": "albedo",
+                        "normalMap": "normals",
+                        "emission": "shared"
+                    }
+                },
+                "stone": {
+                    "values": { "occlusionTexture": "shared" }
+                }
+            },
+            "textures": {
+                "albedo": { "sampler": "s", "source": "i" },
+                "normals": { "sampler": "s", "source": "i" },
+                "shared": { "sampler": "s", "source": "i" },
+                "unused": { "sampler": "s", "source": "i" }
+            }
+        }"#).unwrap();
+        assert_eq!(ColorSpace::Srgb, Texture::color_space("albedo", &gltf));
+        assert_eq!(ColorSpace::Linear, Texture::color_space("normals", &gltf));
+        assert_eq!(ColorSpace::Srgb, Texture::color_space("shared", &gltf));
+        assert_eq!(ColorSpace::Srgb, Texture::color_space("unused", &gltf));
+    }
 }
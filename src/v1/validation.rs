@@ -0,0 +1,600 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde_json::Value;
+
+use v1::Gltf;
+
+/// How thoroughly an asset is validated.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Validation {
+    /// No validation is performed, e.g. for trusted assets on hot paths.
+    Skip,
+
+    /// Only checks that every ID refers to an existing object.
+    Minimal,
+
+    /// Checks conformance to the specification, including accessor types,
+    /// value ranges and the node hierarchy.
+    #[default]
+    Complete,
+}
+
+/// How serious a validation problem is.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// The asset violates the specification.
+    Error,
+
+    /// The asset is valid but likely to be handled incorrectly.
+    Warning,
+
+    /// The asset is valid; the problem is reported for information only.
+    Info,
+}
+
+/// The kind of a validation problem, for filtering by tools.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Code {
+    /// An ID does not refer to an existing object.
+    MissingReference,
+
+    /// A required property is absent.
+    MissingProperty,
+
+    /// An accessor has a component type or kind not allowed for its use.
+    InvalidAccessorType,
+
+    /// A value lies outside its allowed range.
+    OutOfRange,
+
+    /// An offset is not a multiple of the size of the data it addresses.
+    Misaligned,
+
+    /// Two related objects have different element counts.
+    CountMismatch,
+
+    /// Properties are defined together that the specification forbids.
+    Conflict,
+
+    /// A node is the child of more than one node.
+    MultipleParents,
+
+    /// A node is its own ancestor.
+    Cycle,
+
+    /// An object is not used by any scene or animation.
+    Unused,
+
+    /// An attribute semantic is neither standard nor application-specific.
+    InvalidSemantic,
+}
+
+/// A JSON pointer (RFC 6901) to a value within the asset, e.g.
+/// `/animations/walk/channels/0`.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Pointer(String);
+
+impl Pointer {
+    /// Returns the pointer to the root of the asset.
+    pub fn root() -> Self {
+        Pointer(String::new())
+    }
+
+    /// Returns the pointer to the named member of this value.
+    pub fn field(&self, name: &str) -> Self {
+        let name = name.replace('~', "~0").replace('/', "~1");
+        Pointer(format!("{}/{}", self.0, name))
+    }
+
+    /// Returns the pointer to the given element of this array.
+    pub fn index(&self, index: usize) -> Self {
+        Pointer(format!("{}/{}", self.0, index))
+    }
+
+    /// Returns the pointer as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Pointer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A problem found while validating a glTF asset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    /// The location of the offending value.
+    pub pointer: Pointer,
+
+    /// The kind of problem.
+    pub code: Code,
+
+    /// How serious the problem is.
+    pub severity: Severity,
+
+    /// A description of the problem.
+    pub description: String,
+
+    /// The offending value, when there is a single one.
+    pub value: Option<Value>,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} ({:?}) at {}: {}",
+               self.severity, self.code, self.pointer, self.description)?;
+        if let Some(ref value) = self.value {
+            write!(f, ", found {}", value)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of validating an asset.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    /// Every problem found, most serious first.
+    pub errors: Vec<Error>,
+}
+
+impl Report {
+    /// Returns `true` if no problem of `Severity::Error` was found.
+    pub fn is_valid(&self) -> bool {
+        self.errors.iter().all(|error| error.severity != Severity::Error)
+    }
+
+    /// Returns the problems of the given severity.
+    pub fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Error> {
+        self.errors.iter().filter(move |error| error.severity == severity)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for error in &self.errors {
+            writeln!(f, "{}", error)?;
+        }
+        let count = |severity| self.with_severity(severity).count();
+        write!(f, "{} errors, {} warnings, {} infos",
+               count(Severity::Error), count(Severity::Warning), count(Severity::Info))
+    }
+}
+
+/// Validates an asset at the given level without loading any of its buffers
+/// or images.
+pub fn validate(gltf: &Gltf, level: Validation) -> Report {
+    let mut errors = gltf.validate_with(level);
+    errors.sort_by(|a, b| {
+        a.severity.cmp(&b.severity).then_with(|| a.pointer.as_str().cmp(b.pointer.as_str()))
+    });
+    Report { errors }
+}
+
+/// Records every accessor, buffer, image, material and node not used by any
+/// scene or animation, as warnings.
+///
+/// Nodes targeted by animations are used along with their descendants, and
+/// buffers are used by the bufferViews of embedded images and shaders too.
+pub(crate) fn report_unused(gltf: &Gltf, errors: &mut Vec<Error>) {
+    let mut nodes = HashSet::new();
+    let mut pending: Vec<&str> = gltf.scenes
+        .values()
+        .flat_map(|scene| scene.nodes.iter().map(String::as_str))
+        .collect();
+    pending.extend(gltf.animations.values()
+        .flat_map(|animation| &animation.channels)
+        .map(|channel| channel.target.id.as_str()));
+    while let Some(id) = pending.pop() {
+        if let Some(node) = gltf.nodes.get(id) {
+            if nodes.insert(id) {
+                pending.extend(node.children.iter().map(String::as_str));
+            }
+        }
+    }
+
+    let mut accessors = HashSet::new();
+    let mut materials = HashSet::new();
+    for node in nodes.iter().map(|id| &gltf.nodes[*id]) {
+        for mesh in node.meshes.iter().filter_map(|id| gltf.meshes.get(id)) {
+            for primitive in &mesh.primitives {
+                accessors.extend(primitive.attributes.values().map(String::as_str));
+                accessors.extend(primitive.indices.as_deref());
+                materials.insert(primitive.material.as_str());
+            }
+        }
+        if let Some(skin) = node.skin.as_ref().and_then(|id| gltf.skins.get(id)) {
+            accessors.extend(skin.inverse_bind_matrices.as_deref());
+        }
+    }
+    for animation in gltf.animations.values() {
+        accessors.extend(animation.parameters.values().map(String::as_str));
+    }
+
+    let images: HashSet<&str> = materials
+        .iter()
+        .filter_map(|id| gltf.materials.get(*id))
+        .flat_map(|material| material.textures(gltf))
+        .map(|(_, id)| gltf.textures[id].source.as_str())
+        .collect();
+
+    // Embedded images and shaders are stored in bufferViews of their own.
+    let mut buffer_views: Vec<String> = accessors
+        .iter()
+        .filter_map(|id| gltf.accessors.get(*id))
+        .map(|accessor| accessor.buffer_view.clone())
+        .collect();
+    buffer_views.extend(images.iter()
+        .filter_map(|id| gltf.images.get(*id))
+        .filter_map(|image| image.binary())
+        .map(|embedded| embedded.buffer_view));
+    buffer_views.extend(gltf.shaders.values()
+        .filter_map(|shader| shader.binary())
+        .map(|embedded| embedded.buffer_view));
+    let buffers: HashSet<&str> = buffer_views
+        .iter()
+        .filter_map(|id| gltf.buffer_views.get(id))
+        .map(|view| view.buffer.as_str())
+        .collect();
+
+    report_unused_ids(errors, "accessors", &gltf.accessors, &accessors);
+    report_unused_ids(errors, "buffers", &gltf.buffers, &buffers);
+    report_unused_ids(errors, "images", &gltf.images, &images);
+    report_unused_ids(errors, "materials", &gltf.materials, &materials);
+    report_unused_ids(errors, "nodes", &gltf.nodes, &nodes);
+}
+
+fn report_unused_ids<T>(
+    errors: &mut Vec<Error>,
+    dictionary_name: &str,
+    dictionary: &HashMap<String, T>,
+    used: &HashSet<&str>,
+) {
+    let mut unused: Vec<&String> = dictionary
+        .keys()
+        .filter(|id| !used.contains(id.as_str()))
+        .collect();
+    unused.sort();
+    for id in unused {
+        errors.push(Error {
+            pointer: Pointer::root().field(dictionary_name).field(id),
+            code: Code::Unused,
+            severity: Severity::Warning,
+            description: "object is not used by any scene or animation".to_string(),
+            value: None,
+        });
+    }
+}
+
+/// Checks an object against the rules of the glTF specification that cannot
+/// be expressed by its type alone.
+pub trait Validate {
+    /// Validates this object, adding any problems found to `errors`.
+    ///
+    /// `pointer` is the location of this object within the asset.
+    fn validate(&self, gltf: &Gltf, pointer: &Pointer, errors: &mut Vec<Error>);
+}
+
+/// Checks that the IDs held by an object refer to existing objects.
+///
+/// This is implemented for each object with the `impl_references!` macro,
+/// which lists the object's ID fields alongside the dictionary they index.
+pub trait References {
+    /// Validates the IDs held by this object, adding any that do not exist
+    /// to `errors`.
+    fn validate_references(&self, gltf: &Gltf, pointer: &Pointer, errors: &mut Vec<Error>);
+}
+
+impl<T: References> References for Vec<T> {
+    fn validate_references(&self, gltf: &Gltf, pointer: &Pointer, errors: &mut Vec<Error>) {
+        for (i, item) in self.iter().enumerate() {
+            item.validate_references(gltf, &pointer.index(i), errors);
+        }
+    }
+}
+
+impl<T: References> References for HashMap<String, T> {
+    fn validate_references(&self, gltf: &Gltf, pointer: &Pointer, errors: &mut Vec<Error>) {
+        for (id, item) in self {
+            item.validate_references(gltf, &pointer.field(id), errors);
+        }
+    }
+}
+
+/// A property holding the IDs of objects in one dictionary.
+pub trait Ids {
+    /// Reports every ID in this property that is not a key of `dictionary`.
+    fn validate_ids<T>(
+        &self,
+        pointer: Pointer,
+        dictionary: &HashMap<String, T>,
+        errors: &mut Vec<Error>,
+    );
+}
+
+impl Ids for String {
+    fn validate_ids<T>(
+        &self,
+        pointer: Pointer,
+        dictionary: &HashMap<String, T>,
+        errors: &mut Vec<Error>,
+    ) {
+        if !dictionary.contains_key(self) {
+            report_value(errors, Code::MissingReference, pointer, self.as_str(),
+                         "referenced object does not exist".to_string());
+        }
+    }
+}
+
+impl Ids for Option<String> {
+    fn validate_ids<T>(
+        &self,
+        pointer: Pointer,
+        dictionary: &HashMap<String, T>,
+        errors: &mut Vec<Error>,
+    ) {
+        if let Some(ref id) = *self {
+            id.validate_ids(pointer, dictionary, errors);
+        }
+    }
+}
+
+impl Ids for Vec<String> {
+    fn validate_ids<T>(
+        &self,
+        pointer: Pointer,
+        dictionary: &HashMap<String, T>,
+        errors: &mut Vec<Error>,
+    ) {
+        for (i, id) in self.iter().enumerate() {
+            id.validate_ids(pointer.index(i), dictionary, errors);
+        }
+    }
+}
+
+impl Ids for HashMap<String, String> {
+    fn validate_ids<T>(
+        &self,
+        pointer: Pointer,
+        dictionary: &HashMap<String, T>,
+        errors: &mut Vec<Error>,
+    ) {
+        for (key, id) in self {
+            id.validate_ids(pointer.field(key), dictionary, errors);
+        }
+    }
+}
+
+/// Records a specification violation.
+pub fn report(errors: &mut Vec<Error>, code: Code, pointer: Pointer, description: String) {
+    errors.push(Error {
+        pointer,
+        code,
+        severity: Severity::Error,
+        description,
+        value: None,
+    });
+}
+
+/// Records a specification violation caused by a single offending value.
+pub fn report_value<V: Into<Value>>(
+    errors: &mut Vec<Error>,
+    code: Code,
+    pointer: Pointer,
+    value: V,
+    description: String,
+) {
+    errors.push(Error {
+        pointer,
+        code,
+        severity: Severity::Error,
+        description,
+        value: Some(value.into()),
+    });
+}
+
+#[cfg(test)]
+mod test {
+    extern crate serde_json;
+    use super::*;
+
+    #[test]
+    fn it_escapes_pointer_segments() {
+        let pointer = Pointer::root().field("meshes").field("a/b~c").index(2);
+        assert_eq!("/meshes/a~1b~0c/2", pointer.as_str());
+    }
+
+    #[test]
+    fn it_reports_missing_references() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "scene": "missing",
+            "nodes": {
+                "a": { "children": ["b"], "meshes": ["mesh"] },
+                "b": { "skin": "skin" }
+            },
+            "textures": {
+                "t": { "sampler": "s", "source": "i" }
+            }
+        }"#).unwrap();
+        let mut errors = gltf.validate();
+        errors.retain(|error| error.severity == Severity::Error);
+        errors.sort_by(|a, b| a.pointer.as_str().cmp(b.pointer.as_str()));
+        let pointers: Vec<&str> = errors
+            .iter()
+            .map(|error| error.pointer.as_str())
+            .collect();
+        assert_eq!(vec![
+            "/nodes/a/meshes/0",
+            "/nodes/b/skin",
+            "/scene",
+            "/textures/t/sampler",
+            "/textures/t/source",
+        ], pointers);
+        assert!(errors.iter().all(|error| error.code == Code::MissingReference));
+        assert_eq!(Some(Value::from("skin")), errors[1].value);
+    }
+
+    #[test]
+    fn it_validates_at_each_level() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "nodes": {
+                "a": {
+                    "meshes": ["missing"],
+                    "matrix": [1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1],
+                    "scale": [2, 2, 2]
+                }
+            }
+        }"#).unwrap();
+        assert!(gltf.validate_with(Validation::Skip).is_empty());
+        assert_eq!(1, gltf.validate_with(Validation::Minimal).len());
+        let complete = validate(&gltf, Validation::Complete);
+        assert_eq!(2, complete.with_severity(Severity::Error).count());
+    }
+
+    #[test]
+    fn it_builds_a_sorted_report() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "scene": "missing",
+            "nodes": { "a": { "skin": "missing" } }
+        }"#).unwrap();
+        let report = validate(&gltf, Validation::Complete);
+        assert!(!report.is_valid());
+        assert_eq!("/nodes/a/skin", report.errors[0].pointer.as_str());
+        assert_eq!(2, report.with_severity(Severity::Error).count());
+        let text = report.to_string();
+        assert!(text.starts_with("Error (MissingReference) at /nodes/a/skin"));
+        assert!(text.ends_with("2 errors, 1 warnings, 0 infos"));
+        assert!(validate(&Gltf::default(), Validation::Complete).is_valid());
+    }
+
+    #[test]
+    fn it_warns_about_unused_objects() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "accessors": {
+                "used": {
+                    "bufferView": "view", "byteOffset": 0, "componentType": 5126,
+                    "count": 0, "type": "VEC3"
+                },
+                "spare": {
+                    "bufferView": "view", "byteOffset": 0, "componentType": 5126,
+                    "count": 0, "type": "VEC3"
+                }
+            },
+            "buffers": {
+                "buffer": { "uri": "data.bin", "byteLength": 0 },
+                "spare": { "uri": "spare.bin", "byteLength": 0 }
+            },
+            "bufferViews": {
+                "view": { "buffer": "buffer", "byteOffset": 0, "byteLength": 0 }
+            },
+            "meshes": {
+                "mesh": {
+                    "primitives": [
+                        { "attributes": { "POSITION": "used" }, "material": "material" }
+                    ]
+                }
+            },
+            "materials": {
+                "material": {},
+                "spare": {}
+            },
+            "nodes": {
+                "root": { "meshes": ["mesh"] },
+                "orphan": {}
+            },
+            "scenes": {
+                "scene": { "nodes": ["root"] }
+            }
+        }"#).unwrap();
+        let report = validate(&gltf, Validation::Complete);
+        assert!(report.is_valid());
+        let warnings: Vec<&str> = report
+            .with_severity(Severity::Warning)
+            .map(|error| error.pointer.as_str())
+            .collect();
+        assert_eq!(vec![
+            "/accessors/spare",
+            "/buffers/spare",
+            "/materials/spare",
+            "/nodes/orphan",
+        ], warnings);
+    }
+
+    #[test]
+    fn it_counts_animation_targets_and_embedded_data_as_used() {
+        let gltf: Gltf = serde_json::from_str(r#"{
+            "animations": {
+                "walk": {
+                    "channels": [{
+                        "sampler": "sampler",
+                        "target": { "id": "animated", "path": "rotation" }
+                    }]
+                }
+            },
+            "buffers": {
+                "binary_glTF": { "uri": "data:,", "byteLength": 0 }
+            },
+            "bufferViews": {
+                "image_view": { "buffer": "binary_glTF", "byteLength": 0 }
+            },
+            "images": {
+                "image": {
+                    "uri": "data:,",
+                    "extensions": {
+                        "KHR_binary_glTF": {
+                            "bufferView": "image_view",
+                            "mimeType": "image/png",
+                            "width": 1,
+                            "height": 1
+                        }
+                    }
+                }
+            },
+            "materials": {
+                "material": { "technique": "technique" }
+            },
+            "meshes": {
+                "mesh": {
+                    "primitives": [{ "attributes": {}, "material": "material" }]
+                }
+            },
+            "nodes": {
+                "root": { "meshes": ["mesh"] },
+                "animated": {}
+            },
+            "scenes": {
+                "scene": { "nodes": ["root"] }
+            },
+            "techniques": {
+                "technique": {
+                    "program": "program",
+                    "parameters": {
+                        "diffuse": { "type": 35678, "value": "texture" }
+                    }
+                }
+            },
+            "textures": {
+                "texture": { "source": "image", "sampler": "sampler" }
+            }
+        }"#).unwrap();
+        let report = validate(&gltf, Validation::Complete);
+        let unused: Vec<&str> = report.errors
+            .iter()
+            .filter(|error| error.code == Code::Unused)
+            .map(|error| error.pointer.as_str())
+            .collect();
+        assert!(unused.is_empty(), "{:?}", unused);
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright 2017 The gltf Library Developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions of glTF enumerations to raw Vulkan values.
+//!
+//! The values are those of `VkFormat`, `VkFilter`, `VkSamplerMipmapMode`,
+//! `VkSamplerAddressMode`, and `VkPrimitiveTopology`.
+
+use v1::accessor::{ComponentType, Kind};
+use v1::mesh::Mode;
+use v1::texture::{Filter, Wrap};
+
+/// Returns the `VkFormat` of a vertex attribute with the given layout.
+///
+/// Integer components are read as normalized values when `normalized` is
+/// `true`, and converted to floating point unchanged otherwise, as with
+/// `glVertexAttribPointer`. Matrices occupy several attribute locations and
+/// have no single format, so `None` is returned for them.
+pub fn format(component_type: ComponentType, kind: Kind, normalized: bool) -> Option<u32> {
+    // The formats of each component type, ordered by component count.
+    let formats: [u32; 4] = match (component_type, normalized) {
+        (ComponentType::I8, true) => [10, 17, 24, 38],
+        (ComponentType::I8, false) => [12, 19, 26, 40],
+        (ComponentType::U8, true) => [9, 16, 23, 37],
+        (ComponentType::U8, false) => [11, 18, 25, 39],
+        (ComponentType::I16, true) => [71, 78, 85, 92],
+        (ComponentType::I16, false) => [73, 80, 87, 94],
+        (ComponentType::U16, true) => [70, 77, 84, 91],
+        (ComponentType::U16, false) => [72, 79, 86, 93],
+        (ComponentType::I32, _) => [99, 102, 105, 108],
+        (ComponentType::U32, _) => [98, 101, 104, 107],
+        (ComponentType::F32, _) => [100, 103, 106, 109],
+        (ComponentType::F64, _) => [112, 115, 118, 121],
+    };
+    match kind {
+        Kind::Scalar => Some(formats[0]),
+        Kind::Vec2 => Some(formats[1]),
+        Kind::Vec3 => Some(formats[2]),
+        Kind::Vec4 => Some(formats[3]),
+        Kind::Mat2 | Kind::Mat3 | Kind::Mat4 => None,
+    }
+}
+
+/// Returns the `VkFilter` of a magnification or minification filter.
+pub fn filter(filter: Filter) -> u32 {
+    match filter {
+        Filter::Nearest | Filter::NearestMipmapNearest | Filter::NearestMipmapLinear => 0,
+        Filter::Linear | Filter::LinearMipmapNearest | Filter::LinearMipmapLinear => 1,
+    }
+}
+
+/// Returns the `VkSamplerMipmapMode` of a minification filter, or `None` if
+/// the filter does not use mipmaps.
+pub fn mipmap_mode(filter: Filter) -> Option<u32> {
+    match filter {
+        Filter::Nearest | Filter::Linear => None,
+        Filter::NearestMipmapNearest | Filter::LinearMipmapNearest => Some(0),
+        Filter::NearestMipmapLinear | Filter::LinearMipmapLinear => Some(1),
+    }
+}
+
+/// Returns the `VkSamplerAddressMode` of a wrapping mode.
+pub fn address_mode(wrap: Wrap) -> u32 {
+    match wrap {
+        Wrap::Repeat => 0,
+        Wrap::MirroredRepeat => 1,
+        Wrap::ClampToEdge => 2,
+    }
+}
+
+/// Returns the `VkPrimitiveTopology` of a primitive mode.
+///
+/// Vulkan has no line loops, so `None` is returned for `Mode::LineLoop`.
+pub fn primitive_topology(mode: Mode) -> Option<u32> {
+    match mode {
+        Mode::Points => Some(0),
+        Mode::Lines => Some(1),
+        Mode::LineLoop => None,
+        Mode::LineStrip => Some(2),
+        Mode::Triangles => Some(3),
+        Mode::TriangleStrip => Some(4),
+        Mode::TriangleFan => Some(5),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_maps_vertex_formats() {
+        // VK_FORMAT_R32G32B32_SFLOAT
+        assert_eq!(Some(106), format(ComponentType::F32, Kind::Vec3, false));
+        // VK_FORMAT_R8G8B8A8_UNORM
+        assert_eq!(Some(37), format(ComponentType::U8, Kind::Vec4, true));
+        // VK_FORMAT_R16G16_USCALED
+        assert_eq!(Some(79), format(ComponentType::U16, Kind::Vec2, false));
+        assert_eq!(None, format(ComponentType::F32, Kind::Mat4, false));
+    }
+
+    #[test]
+    fn it_maps_sampler_state() {
+        assert_eq!(1, filter(Filter::LinearMipmapNearest));
+        assert_eq!(Some(0), mipmap_mode(Filter::LinearMipmapNearest));
+        assert_eq!(None, mipmap_mode(Filter::Linear));
+        assert_eq!(2, address_mode(Wrap::ClampToEdge));
+        assert_eq!(None, primitive_topology(Mode::LineLoop));
+    }
+}